@@ -3,9 +3,7 @@ use std::{
     pin::Pin,
 };
 
-use uuid::Uuid;
-
-use crate::{Entity, Error};
+use crate::{Entity, EntityId, Error};
 
 pub type LoadResult<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
 
@@ -13,19 +11,57 @@ pub type LoadResult<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Sen
 pub trait EntityAdapter: Entity {
     type Provider: Any + Send + Sync;
 
-    fn load_data(id: Uuid, provider: &Self::Provider) -> LoadResult<Self>
+    /// Name of the provider registered with `Engine::with_named_provider`, eg. `"analytics"` when
+    /// there are several `PgPool`s, the one registered by its type alone when `None`
+    const PROVIDER_NAME: Option<&'static str> = None;
+
+    /// Identifier of the entity, eg. `Uuid`, `i64`, a `String` slug or a composite key
+    ///
+    /// Converted from the `EntityId` of the request, a mismatch fails with `Error::InvalidEntityId`
+    type Id: TryFrom<EntityId> + Send;
+
+    /// Fails with `Error::NotFound` when there's no such entity, any other error is a load failure
+    fn load_data(id: Self::Id, provider: &Self::Provider) -> LoadResult<'_, Self>
     where
         Self: Sized;
+
+    /// Load only the `fields` used by the rules, eg. to `SELECT` only the needed columns
+    ///
+    /// Fields that aren't loaded can be left as default, loads everything unless overridden.
+    /// Never called with no fields, `load_data` is used instead
+    fn load_fields<'a>(
+        id: Self::Id,
+        fields: &'a [&'a str],
+        provider: &'a Self::Provider,
+    ) -> LoadResult<'a, Self>
+    where
+        Self: Sized,
+    {
+        let _ = fields;
+
+        Self::load_data(id, provider)
+    }
 }
 
 pub(crate) trait DynAdapter: Send + Sync {
     /// Which provider does adapter need?
     fn provider_type(&self) -> TypeId;
 
+    /// Which named provider does adapter need, if any?
+    fn provider_name(&self) -> Option<&'static str>;
+
     /// Load Entity
     fn load<'a>(
         &self,
-        id: Uuid,
+        id: EntityId,
+        provider: &'a (dyn Any + Send + Sync),
+    ) -> LoadResult<'a, Box<dyn Entity>>;
+
+    /// Load Entity with only the needed fields
+    fn load_fields<'a>(
+        &self,
+        id: EntityId,
+        fields: &'a [&'a str],
         provider: &'a (dyn Any + Send + Sync),
     ) -> LoadResult<'a, Box<dyn Entity>>;
 }
@@ -38,9 +74,13 @@ where
         TypeId::of::<T::Provider>()
     }
 
+    fn provider_name(&self) -> Option<&'static str> {
+        T::PROVIDER_NAME
+    }
+
     fn load<'a>(
         &self,
-        id: Uuid,
+        id: EntityId,
         provider: &'a (dyn Any + Send + Sync),
     ) -> LoadResult<'a, Box<dyn Entity>> {
         Box::pin(async move {
@@ -50,9 +90,33 @@ where
                 .ok_or(Error::ProviderNotFound)?;
 
             // Load the entity
-            let entity = T::load_data(id, provider).await?;
+            let entity = T::load_data(convert_id::<T>(id)?, provider).await?;
 
             Ok(Box::new(entity) as Box<dyn Entity>)
         })
     }
+
+    fn load_fields<'a>(
+        &self,
+        id: EntityId,
+        fields: &'a [&'a str],
+        provider: &'a (dyn Any + Send + Sync),
+    ) -> LoadResult<'a, Box<dyn Entity>> {
+        Box::pin(async move {
+            // Get the right provider
+            let provider = provider
+                .downcast_ref::<T::Provider>()
+                .ok_or(Error::ProviderNotFound)?;
+
+            // Load the entity
+            let entity = T::load_fields(convert_id::<T>(id)?, fields, provider).await?;
+
+            Ok(Box::new(entity) as Box<dyn Entity>)
+        })
+    }
+}
+
+/// Id of the request in the adapter's own form
+fn convert_id<T: EntityAdapter>(id: EntityId) -> Result<T::Id, Error> {
+    T::Id::try_from(id.clone()).map_err(|_| id.invalid::<T::Id>())
 }
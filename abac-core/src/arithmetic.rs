@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+
+use crate::{Error, Number, SideRule};
+
+/// Arithmetic on either side of a rule, eg. `{ "Arithmetic": { "left": ..., "operator": "Multiply", "right": ... } }`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Expression {
+    pub(crate) left: SideRule,
+    pub(crate) operator: ArithmeticOperator,
+    pub(crate) right: SideRule,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ArithmeticOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+impl ArithmeticOperator {
+    /// Apply to both numbers, integers stay integers unless one side is a float
+    pub(crate) fn apply(&self, left: &Value, right: &Value) -> Result<Value, Error> {
+        let number = |value: &Value| {
+            Number::from_value(value).ok_or_else(|| Error::InvalidOperands {
+                operator: format!("{self:?}"),
+                reason: format!("{value:?} isn't a number"),
+            })
+        };
+
+        match (number(left)?, number(right)?) {
+            (Number::Int(left), Number::Int(right)) => {
+                let result = match self {
+                    ArithmeticOperator::Add => left.checked_add(right),
+                    ArithmeticOperator::Subtract => left.checked_sub(right),
+                    ArithmeticOperator::Multiply => left.checked_mul(right),
+                    ArithmeticOperator::Divide | ArithmeticOperator::Remainder if right == 0 => {
+                        return Err(Error::DivisionByZero);
+                    }
+                    ArithmeticOperator::Divide => left.checked_div(right),
+                    ArithmeticOperator::Remainder => left.checked_rem(right),
+                };
+
+                // Attributes are at most 64 bits wide
+                match result {
+                    Some(n) if n < 0 => i64::try_from(n).ok().map(Value::I64),
+                    Some(n) => u64::try_from(n).ok().map(Value::U64),
+                    None => None,
+                }
+                .ok_or_else(|| Error::ArithmeticOverflow(format!("{self:?}")))
+            }
+            (left, right) => {
+                let (left, right) = (left.as_f64(), right.as_f64());
+                let result = match self {
+                    ArithmeticOperator::Add => left + right,
+                    ArithmeticOperator::Subtract => left - right,
+                    ArithmeticOperator::Multiply => left * right,
+                    ArithmeticOperator::Divide | ArithmeticOperator::Remainder if right == 0.0 => {
+                        return Err(Error::DivisionByZero);
+                    }
+                    ArithmeticOperator::Divide => left / right,
+                    ArithmeticOperator::Remainder => left % right,
+                };
+
+                match result.is_finite() {
+                    true => Ok(Value::F64(result)),
+                    false => Err(Error::ArithmeticOverflow(format!("{self:?}"))),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    ArithmeticOperator, AttrPath, Cidr, Condition, Duration, Error, Expression, Operator,
+    PermissionRule, Quantifier, RelationRule, RoleRule, Rule, Rules, SideRule, Target,
+};
+
+/// Typed reference to an Entity's field, generated by `#[derive(Entity)]` (eg. `User::AGE`)
+///
+/// `E` is the entity owning the field and `T` is the field's type
+pub struct Attr<E, T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> (E, T)>,
+}
+
+impl<E, T> Attr<E, T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<E, T> Clone for Attr<E, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E, T> Copy for Attr<E, T> {}
+
+/// One side of a rule that is being built, `T` keeps both sides on the same type
+pub struct Operand<T> {
+    side: SideRule,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Operand<T> {
+    fn new(side: SideRule) -> Self {
+        Self {
+            side,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Literal of any serializable type, eg. an enum, failing when it doesn't serialize
+    pub fn literal(value: &T) -> Result<Self, Error>
+    where
+        T: Serialize,
+    {
+        Ok(Operand::new(SideRule::Literal(serde_value::to_value(
+            value,
+        )?)))
+    }
+
+    fn compare<U>(self, operator: Operator, right: impl Into<Operand<U>>) -> Rule {
+        Rule {
+            left: self.side,
+            operator,
+            right: right.into().side,
+        }
+    }
+
+    pub fn eq(self, right: impl Into<Operand<T>>) -> Rule {
+        self.compare(Operator::Equal, right)
+    }
+
+    pub fn gt(self, right: impl Into<Operand<T>>) -> Rule {
+        self.compare(Operator::Greater, right)
+    }
+
+    pub fn lt(self, right: impl Into<Operand<T>>) -> Rule {
+        self.compare(Operator::Less, right)
+    }
+
+    pub fn ge(self, right: impl Into<Operand<T>>) -> Rule {
+        self.compare(Operator::GreaterEqual, right)
+    }
+
+    pub fn le(self, right: impl Into<Operand<T>>) -> Rule {
+        self.compare(Operator::LessEqual, right)
+    }
+
+    /// Temporal operands may have different types, eg. a date before a timestamp
+    pub fn before<U>(self, right: impl Into<Operand<U>>) -> Rule {
+        self.compare(Operator::Before, right)
+    }
+
+    pub fn after<U>(self, right: impl Into<Operand<U>>) -> Rule {
+        self.compare(Operator::After, right)
+    }
+
+    /// Both timestamps are at most `duration` apart
+    pub fn within<U>(self, duration: Duration, right: impl Into<Operand<U>>) -> Rule {
+        self.compare(Operator::Within(duration), right)
+    }
+
+    /// IP address inside one of the blocks
+    pub fn in_cidr(self, blocks: impl IntoIterator<Item = Cidr>) -> Rule {
+        self.compare::<T>(
+            Operator::InCidr,
+            Operand::new(SideRule::Cidr(blocks.into_iter().collect())),
+        )
+    }
+
+    /// IP address outside every block
+    pub fn not_in_cidr(self, blocks: impl IntoIterator<Item = Cidr>) -> Rule {
+        self.compare::<T>(
+            Operator::NotInCidr,
+            Operand::new(SideRule::Cidr(blocks.into_iter().collect())),
+        )
+    }
+
+    /// Custom operator registered on the `Engine`, both sides may have different types
+    pub fn is<U>(self, operator: impl Into<String>, right: impl Into<Operand<U>>) -> Rule {
+        self.compare(Operator::Custom(operator.into()), right)
+    }
+}
+
+macro_rules! impl_arithmetic {
+    ($($trait:ident::$method:ident => $operator:ident),*) => {
+        $(
+            /// Arithmetic between operands of the same type, eg. `subject(User::LIMIT) * 2`
+            impl<T, R: Into<Operand<T>>> std::ops::$trait<R> for Operand<T> {
+                type Output = Operand<T>;
+
+                fn $method(self, right: R) -> Self::Output {
+                    Operand::new(SideRule::Arithmetic(Box::new(Expression {
+                        left: self.side,
+                        operator: ArithmeticOperator::$operator,
+                        right: right.into().side,
+                    })))
+                }
+            }
+        )*
+    };
+}
+
+impl_arithmetic!(
+    Add::add => Add,
+    Sub::sub => Subtract,
+    Mul::mul => Multiply,
+    Div::div => Divide,
+    Rem::rem => Remainder
+);
+
+/// Value converted into a literal without failing, other types go through `Operand::literal`
+pub trait Literal {
+    fn to_literal(&self) -> Value;
+}
+
+macro_rules! impl_literal {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl Literal for $ty {
+                fn to_literal(&self) -> Value {
+                    Value::$variant(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_literal!(
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    char => Char
+);
+
+impl Literal for String {
+    fn to_literal(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl Literal for Uuid {
+    fn to_literal(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl<T: Literal> Literal for Option<T> {
+    fn to_literal(&self) -> Value {
+        Value::Option(self.as_ref().map(|value| Box::new(value.to_literal())))
+    }
+}
+
+impl<T: Literal> Literal for Vec<T> {
+    fn to_literal(&self) -> Value {
+        Value::Seq(self.iter().map(Literal::to_literal).collect())
+    }
+}
+
+/// Literal of the same type as the other side
+impl<T: Literal> From<T> for Operand<T> {
+    fn from(value: T) -> Self {
+        Operand::new(SideRule::Literal(value.to_literal()))
+    }
+}
+
+/// Subject's field
+pub fn subject<E, T>(attr: Attr<E, T>) -> Operand<T> {
+    Operand::new(SideRule::Subject(attr.into()))
+}
+
+/// Object's (resource) field
+pub fn object<E, T>(attr: Attr<E, T>) -> Operand<T> {
+    Operand::new(SideRule::Object(attr.into()))
+}
+
+/// Placeholder of a `Template`, bound to a value of the same type when instantiated
+pub fn param<T>(name: impl Into<String>) -> Operand<T> {
+    Operand::new(SideRule::Param(name.into()))
+}
+
+/// Field of another entity of the request, referenced by its alias, eg. `entity("tenant", Tenant::PLAN)`
+pub fn entity<E, T>(alias: impl Into<String>, attr: Attr<E, T>) -> Operand<T> {
+    Operand::new(SideRule::Entity(alias.into(), attr.into()))
+}
+
+/// Field of the element bound by `any`/`all`
+pub fn element<E, T>(attr: Attr<E, T>) -> Operand<T> {
+    Operand::new(SideRule::Element(Some(attr.into())))
+}
+
+/// The element bound by `any`/`all` itself, eg. each tag of a `Vec<String>`
+pub fn each<T>() -> Operand<T> {
+    Operand::new(SideRule::Element(None))
+}
+
+/// At least one element of the collection passes the rules
+pub fn any<T>(collection: Operand<T>, rules: impl Into<Rules>) -> Condition {
+    Condition::Any(Quantifier {
+        collection: collection.side,
+        rules: rules.into(),
+    })
+}
+
+/// Every element of the collection passes the rules
+pub fn all<T>(collection: Operand<T>, rules: impl Into<Rules>) -> Condition {
+    Condition::All(Quantifier {
+        collection: collection.side,
+        rules: rules.into(),
+    })
+}
+
+/// Condition declared once in the `Definitions`
+pub fn named(name: impl Into<String>) -> Condition {
+    Condition::Named(name.into())
+}
+
+/// Whole policy stored on the `Engine`
+pub fn policy(id: impl Into<String>) -> Condition {
+    Condition::Ref(id.into())
+}
+
+/// `subject` has `relation` on `object` according to the relationship tuples
+pub fn has_relation(subject: Target, relation: impl Into<String>, object: Target) -> RelationRule {
+    RelationRule {
+        subject,
+        relation: relation.into(),
+        object,
+    }
+}
+
+/// Subject's role `attribute` holds `role`, directly or through inheritance
+///
+/// Eg. `has_role(User::ROLES, "admin")`, or a parsed `AttrPath` for nested attributes
+pub fn has_role(attribute: impl Into<AttrPath>, role: impl Into<String>) -> RoleRule {
+    RoleRule {
+        attribute: attribute.into(),
+        role: role.into(),
+    }
+}
+
+/// One of the subject's roles in `attribute` is granted `permission`
+pub fn has_permission(
+    attribute: impl Into<AttrPath>,
+    permission: impl Into<String>,
+) -> PermissionRule {
+    PermissionRule {
+        attribute: attribute.into(),
+        permission: permission.into(),
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AttrPath, Condition, Error, Policies, Policy, Quantifier, RelationRule, Rules, Target,
+};
+
+/// Named conditions declared once and referenced from any `Rules` with `{ "Named": "is_owner" }`
+///
+/// Deserializes from a map of name to rules, eg. `{ "is_owner": [[...]], "same_tenant": [[...]] }`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Definitions(HashMap<String, Rules>);
+
+/// Named condition replaced by its rules, the name is kept for explanation
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Definition {
+    pub(crate) name: String,
+    pub(crate) rules: Rules,
+}
+
+impl Definitions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare `name`, it may reference other named conditions
+    #[inline]
+    pub fn define(mut self, name: impl Into<String>, rules: impl Into<Rules>) -> Self {
+        self.0.insert(name.into(), rules.into());
+
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Rules> {
+        self.0.get(name)
+    }
+
+    /// Add every definition of `other`, replacing the ones with the same name
+    pub fn extend(&mut self, other: Definitions) {
+        self.0.extend(other.0);
+    }
+
+    /// Every definition must only reference existing ones, without cycles
+    pub fn check(&self) -> Result<(), Error> {
+        self.0
+            .keys()
+            .try_for_each(|name| Inliner::new(self, None).expand_named(name).map(|_| ()))
+    }
+
+    /// Replace every named condition by its rules, recursively
+    ///
+    /// Policy references are left as they are, the engine resolves them along with the definitions
+    pub fn inline(&self, rules: &Rules) -> Result<Rules, Error> {
+        Inliner::new(self, None).inline_rules(rules)
+    }
+}
+
+/// Replaces named conditions, and policy references when there are policies, by their rules
+pub(crate) struct Inliner<'a> {
+    definitions: &'a Definitions,
+    policies: Option<&'a Policies>,
+    /// Definitions and policies being inlined, to catch cycles
+    stack: Vec<Inlining<'a>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Inlining<'a> {
+    Named(&'a str),
+    Policy(&'a str),
+}
+
+impl<'a> Inliner<'a> {
+    pub(crate) fn new(definitions: &'a Definitions, policies: Option<&'a Policies>) -> Self {
+        Self {
+            definitions,
+            policies,
+            stack: Vec::new(),
+        }
+    }
+
+    pub(crate) fn inline_rules(&mut self, rules: &Rules) -> Result<Rules, Error> {
+        let groups = rules
+            .0
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|condition| self.inline_condition(condition))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Rules(groups))
+    }
+
+    fn inline_condition(&mut self, condition: &Condition) -> Result<Condition, Error> {
+        let condition = match condition {
+            Condition::Named(name) => Condition::Inlined(self.expand_named(name)?),
+            Condition::Ref(id) if self.policies.is_some() => {
+                Condition::Included(self.expand_policy(id)?)
+            }
+            Condition::Inlined(definition) => Condition::Inlined(Definition {
+                name: definition.name.clone(),
+                rules: self.inline_rules(&definition.rules)?,
+            }),
+            Condition::Included(policy) => Condition::Included(Policy {
+                id: policy.id.clone(),
+                rules: self.inline_rules(&policy.rules)?,
+            }),
+            Condition::Any(quantifier) => Condition::Any(self.inline_quantifier(quantifier)?),
+            Condition::All(quantifier) => Condition::All(self.inline_quantifier(quantifier)?),
+            condition => condition.clone(),
+        };
+
+        Ok(condition)
+    }
+
+    fn inline_quantifier(&mut self, quantifier: &Quantifier) -> Result<Quantifier, Error> {
+        Ok(Quantifier {
+            collection: quantifier.collection.clone(),
+            rules: self.inline_rules(&quantifier.rules)?,
+        })
+    }
+
+    pub(crate) fn expand_named(&mut self, name: &str) -> Result<Definition, Error> {
+        let (name, rules) = self
+            .definitions
+            .0
+            .get_key_value(name)
+            .ok_or_else(|| Error::UnknownCondition(name.to_string()))?;
+        let rules = self.expand(Inlining::Named(name), rules)?;
+
+        Ok(Definition {
+            name: name.clone(),
+            rules,
+        })
+    }
+
+    pub(crate) fn expand_policy(&mut self, id: &str) -> Result<Policy, Error> {
+        let (id, rules) = self
+            .policies
+            .and_then(|policies| policies.get_key_value(id))
+            .ok_or_else(|| Error::UnknownPolicy(id.to_string()))?;
+        let rules = self.expand(Inlining::Policy(id), rules)?;
+
+        Ok(Policy {
+            id: id.clone(),
+            rules,
+        })
+    }
+
+    fn expand(&mut self, entry: Inlining<'a>, rules: &Rules) -> Result<Rules, Error> {
+        if self.stack.contains(&entry) {
+            return Err(cycle(&self.stack, entry));
+        }
+
+        self.stack.push(entry);
+        let rules = self.inline_rules(rules);
+        self.stack.pop();
+
+        rules
+    }
+}
+
+/// Cycle from the first time `entry` was entered back to it, eg. `a -> b -> a`
+fn cycle(stack: &[Inlining<'_>], entry: Inlining<'_>) -> Error {
+    let start = stack.iter().position(|e| *e == entry).unwrap_or_default();
+    let cycle = stack[start..]
+        .iter()
+        .chain([&entry])
+        .map(|e| match e {
+            Inlining::Named(name) | Inlining::Policy(name) => *name,
+        })
+        .collect::<Vec<_>>()
+        .join(" -> ");
+
+    match entry {
+        Inlining::Named(_) => Error::ConditionCycle(cycle),
+        Inlining::Policy(_) => Error::PolicyCycle(cycle),
+    }
+}
+
+/// Definitions and policies checked once for unknown references and cycles
+///
+/// Keeps what each one reads, its references included, so evaluating only looks it up
+/// instead of inlining them
+#[derive(Debug, Default)]
+pub(crate) struct Compiled {
+    named: HashMap<String, Reads>,
+    policies: HashMap<String, Reads>,
+}
+
+/// Attribute paths and relations read by rules, along with the ones of their references
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Reads {
+    pub(crate) paths: Vec<(Target, AttrPath)>,
+    pub(crate) relations: Vec<RelationRule>,
+}
+
+impl Reads {
+    fn of(rules: &Rules) -> Self {
+        Self {
+            paths: rules
+                .paths()
+                .into_iter()
+                .map(|(target, path)| (target, path.clone()))
+                .collect(),
+            relations: rules.relations().into_iter().cloned().collect(),
+        }
+    }
+
+    fn extend(&mut self, other: Reads) {
+        for path in other.paths {
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+        for relation in other.relations {
+            if !self.relations.contains(&relation) {
+                self.relations.push(relation);
+            }
+        }
+    }
+}
+
+impl Compiled {
+    pub(crate) fn new(definitions: &Definitions, policies: &Policies) -> Result<Self, Error> {
+        let mut compiler = Compiler {
+            definitions,
+            policies,
+            stack: Vec::new(),
+            reads: HashMap::new(),
+        };
+
+        // Sorted so the same cycle is always reported from the same entry
+        let mut names = definitions.0.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            compiler.visit(Inlining::Named(name))?;
+        }
+        let mut ids = policies.ids().collect::<Vec<_>>();
+        ids.sort();
+        for id in ids {
+            compiler.visit(Inlining::Policy(id))?;
+        }
+
+        let mut compiled = Self::default();
+        for (entry, reads) in compiler.reads {
+            match entry {
+                Inlining::Named(name) => compiled.named.insert(name.to_string(), reads),
+                Inlining::Policy(id) => compiled.policies.insert(id.to_string(), reads),
+            };
+        }
+
+        Ok(compiled)
+    }
+
+    /// What a named condition or policy reads, through its references too
+    pub(crate) fn reads(&self, entry: Inlining<'_>) -> Result<&Reads, Error> {
+        match entry {
+            Inlining::Named(name) => self
+                .named
+                .get(name)
+                .ok_or_else(|| Error::UnknownCondition(name.to_string())),
+            Inlining::Policy(id) => self
+                .policies
+                .get(id)
+                .ok_or_else(|| Error::UnknownPolicy(id.to_string())),
+        }
+    }
+}
+
+/// Walks each definition and policy once, a shared reference is only visited the first time
+struct Compiler<'a> {
+    definitions: &'a Definitions,
+    policies: &'a Policies,
+    stack: Vec<Inlining<'a>>,
+    reads: HashMap<Inlining<'a>, Reads>,
+}
+
+impl<'a> Compiler<'a> {
+    fn visit(&mut self, entry: Inlining<'a>) -> Result<Reads, Error> {
+        if let Some(reads) = self.reads.get(&entry) {
+            return Ok(reads.clone());
+        }
+        if self.stack.contains(&entry) {
+            return Err(cycle(&self.stack, entry));
+        }
+
+        let rules = match entry {
+            Inlining::Named(name) => self
+                .definitions
+                .get(name)
+                .ok_or_else(|| Error::UnknownCondition(name.to_string()))?,
+            Inlining::Policy(id) => self
+                .policies
+                .get(id)
+                .ok_or_else(|| Error::UnknownPolicy(id.to_string()))?,
+        };
+
+        self.stack.push(entry);
+        let mut reads = Reads::of(rules);
+        for reference in rules.references() {
+            reads.extend(self.visit(reference)?);
+        }
+        self.stack.pop();
+
+        self.reads.insert(entry, reads.clone());
+
+        Ok(reads)
+    }
+}
@@ -1,30 +1,175 @@
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
+    sync::{Arc, OnceLock},
 };
 
 use uuid::Uuid;
 
-use crate::{DynAdapter, EmptyEntity, Entity, EntityAdapter, Error, Rules, evaluate};
+use crate::{
+    AliasedEntity, AttributeResolver, AttributeSchema, Clock, Compiled, Condition, Context,
+    CustomFunction, Definitions, DynAdapter, DynResolver, EmptyEntity, Entity, EntityAdapter,
+    EntityId, EntitySchema, Error, Inliner, LinkedEntity, LoadedEntities, ObjectRef, PathSegment,
+    Policies, RelationCheck, RelationRule, Relations, RoleGraph, Rules, Schema, SystemClock,
+    Target, evaluate_rules,
+};
+
+/// How many references a rule path may traverse by default, eg. `project.organization.owner` is 2
+pub const DEFAULT_MAX_DEPTH: usize = 3;
 
 /// Which Entity to evaluate?
 #[derive(Clone)]
 pub struct EvaluateEntity<'a> {
-    name: &'a str,
-    id: Option<Uuid>,
+    pub(crate) name: &'a str,
+    id: Option<EntityId>,
+    preloaded: Option<Preloaded<'a>>,
+}
+
+/// Entity the caller already has in memory, eg. the user of the session
+#[derive(Clone)]
+enum Preloaded<'a> {
+    Borrowed(&'a dyn Entity),
+    Shared(Arc<dyn Entity>),
 }
 
 impl<'a> EvaluateEntity<'a> {
+    /// Loaded through the adapter registered as `name`, an empty entity without id
     pub fn new(name: &'a str, id: Option<Uuid>) -> Self {
-        Self { name, id }
+        Self {
+            name,
+            id: id.map(Into::into),
+            preloaded: None,
+        }
+    }
+
+    /// Loaded through the adapter registered as `name` by an id of its own `Id`, eg. `42` or `"slug"`
+    pub fn by_id(name: &'a str, id: impl Into<EntityId>) -> Self {
+        Self {
+            name,
+            id: Some(id.into()),
+            preloaded: None,
+        }
+    }
+
+    /// Already loaded entity, the id is still used for its relations and resolved attributes
+    pub fn preloaded(name: &'a str, id: Option<Uuid>, entity: &'a dyn Entity) -> Self {
+        Self {
+            name,
+            id: id.map(Into::into),
+            preloaded: Some(Preloaded::Borrowed(entity)),
+        }
+    }
+
+    /// Already loaded entity shared with the caller
+    pub fn shared(name: &'a str, id: Option<Uuid>, entity: Arc<dyn Entity>) -> Self {
+        Self {
+            name,
+            id: id.map(Into::into),
+            preloaded: Some(Preloaded::Shared(entity)),
+        }
+    }
+
+    /// Id of a preloaded entity that isn't a UUID, eg. `42` or `"slug"`
+    #[inline]
+    pub fn with_id(mut self, id: impl Into<EntityId>) -> Self {
+        self.id = Some(id.into());
+
+        self
+    }
+
+    /// Neither an id nor a preloaded entity, evaluated as an empty entity
+    fn is_empty(&self) -> bool {
+        self.id.is_none() && self.preloaded.is_none()
+    }
+
+    /// Identity in the relation graph, eg. `user:<id>`
+    fn object_ref(&self) -> Option<ObjectRef> {
+        self.id
+            .as_ref()
+            .map(|id| ObjectRef::new(self.name, id.to_string()))
+    }
+}
+
+/// Entities of a single evaluation, the subject and resource along with any other ones
+/// referenced by their alias from the rules, eg. the tenant or the parent folder
+#[derive(Clone)]
+pub struct EvaluateRequest<'a> {
+    pub(crate) subject: EvaluateEntity<'a>,
+    pub(crate) resource: EvaluateEntity<'a>,
+    pub(crate) entities: Vec<(&'a str, EvaluateEntity<'a>)>,
+}
+
+impl<'a> EvaluateRequest<'a> {
+    pub fn new(subject: EvaluateEntity<'a>, resource: EvaluateEntity<'a>) -> Self {
+        Self {
+            subject,
+            resource,
+            entities: Vec::new(),
+        }
+    }
+
+    /// Entity referenced from the rules as `alias`, eg. `entity("tenant", Tenant::PLAN)`
+    #[inline]
+    pub fn with_entity(mut self, alias: &'a str, entity: EvaluateEntity<'a>) -> Self {
+        self.entities.push((alias, entity));
+
+        self
+    }
+}
+
+/// Outcome of an evaluation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Permit,
+    Deny,
+    /// The rules don't apply, eg. the resource doesn't exist
+    NotApplicable,
+}
+
+/// What a resource that doesn't exist evaluates to, eg. to answer 404 instead of 403
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingResource {
+    /// Fail with `Error::EntityNotFound`
+    #[default]
+    Error,
+    Deny,
+    NotApplicable,
+}
+
+impl MissingResource {
+    fn decide(self, err: Error) -> Result<Decision, Error> {
+        match self {
+            MissingResource::Error => Err(err),
+            MissingResource::Deny => Ok(Decision::Deny),
+            MissingResource::NotApplicable => Ok(Decision::NotApplicable),
+        }
     }
 }
 
-#[derive(Default)]
 pub struct Engine {
     pub(crate) entities: HashMap<&'static str, Box<dyn Entity>>,
     pub(crate) adapters: HashMap<&'static str, Box<dyn DynAdapter>>,
-    pub(crate) providers: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    /// Keyed by type along with the name, `None` for the ones registered by their type alone
+    pub(crate) providers: HashMap<(TypeId, Option<&'static str>), Box<dyn Any + Send + Sync>>,
+    pub(crate) max_depth: usize,
+    pub(crate) missing_resource: MissingResource,
+    pub(crate) subject_required: bool,
+    pub(crate) anonymous: Option<(&'static str, Arc<dyn Entity>)>,
+    pub(crate) relations: Option<Relations>,
+    pub(crate) roles: Option<RoleGraph>,
+    pub(crate) resolvers: HashMap<&'static str, HashMap<&'static str, Box<dyn DynResolver>>>,
+    pub(crate) functions: HashMap<&'static str, Box<dyn CustomFunction>>,
+    pub(crate) clock: Box<dyn Clock>,
+    pub(crate) definitions: Definitions,
+    pub(crate) policies: Policies,
+    /// Built on first use, reset whenever a definition or policy is added
+    pub(crate) compiled: OnceLock<Compiled>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Engine {
@@ -33,6 +178,18 @@ impl Engine {
             entities: HashMap::new(),
             adapters: HashMap::new(),
             providers: HashMap::new(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            missing_resource: MissingResource::default(),
+            subject_required: true,
+            anonymous: None,
+            relations: None,
+            roles: None,
+            resolvers: HashMap::new(),
+            functions: HashMap::new(),
+            clock: Box::new(SystemClock),
+            definitions: Definitions::new(),
+            policies: Policies::new(),
+            compiled: OnceLock::new(),
         }
     }
 
@@ -47,16 +204,266 @@ impl Engine {
         self
     }
 
+    /// Provider used by the adapters and resolvers without a `PROVIDER_NAME`, replacing the one
+    /// of the same type
     #[inline]
     pub fn with_provider<P>(mut self, provider: P) -> Self
     where
         P: Any + Send + Sync + 'static,
     {
-        self.providers.insert(TypeId::of::<P>(), Box::new(provider));
+        self.providers
+            .insert((TypeId::of::<P>(), None), Box::new(provider));
+
+        self
+    }
+
+    /// Provider used by the adapters and resolvers declaring `name` as their `PROVIDER_NAME`,
+    /// eg. a second `PgPool` for analytics next to the primary one
+    ///
+    /// Several named providers of the same type are kept, one of the same type and name is replaced
+    #[inline]
+    pub fn with_named_provider<P>(mut self, name: &'static str, provider: P) -> Self
+    where
+        P: Any + Send + Sync + 'static,
+    {
+        self.providers
+            .insert((TypeId::of::<P>(), Some(name)), Box::new(provider));
+
+        self
+    }
+
+    /// Resolve `attribute` of the `entity` through the resolver instead of the adapter
+    #[inline]
+    pub fn register_resolver<R>(
+        mut self,
+        entity: &'static str,
+        attribute: &'static str,
+        resolver: R,
+    ) -> Self
+    where
+        R: AttributeResolver + 'static,
+    {
+        self.resolvers
+            .entry(entity)
+            .or_default()
+            .insert(attribute, Box::new(resolver));
+
+        self
+    }
+
+    /// Function callable by its name from the rules, shadowing the built-in of the same name
+    #[inline]
+    pub fn register_function<F>(mut self, function: F) -> Self
+    where
+        F: CustomFunction + 'static,
+    {
+        self.functions.insert(function.name(), Box::new(function));
+
+        self
+    }
+
+    /// How many references a rule path may traverse
+    #[inline]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+
+        self
+    }
+
+    /// What a resource that doesn't exist evaluates to, fails with `Error::EntityNotFound` by default
+    #[inline]
+    pub fn with_missing_resource(mut self, missing_resource: MissingResource) -> Self {
+        self.missing_resource = missing_resource;
+
+        self
+    }
+
+    /// Whether a subject without id fails with `Error::SubjectNotFound`, which it does by default
+    ///
+    /// Unauthenticated requests should rather use the principal registered with `with_anonymous`
+    #[inline]
+    pub fn with_subject_required(mut self, subject_required: bool) -> Self {
+        self.subject_required = subject_required;
+
+        self
+    }
+
+    /// Principal of unauthenticated requests registered as `name`, with its own attributes,
+    /// eg. a guest with `authenticated: false`
+    ///
+    /// An entity already registered as `name` keeps its schema, eg. a guest `user`
+    #[inline]
+    pub fn with_anonymous<E>(mut self, name: &'static str, entity: E) -> Self
+    where
+        E: Entity + 'static,
+    {
+        let entity: Arc<dyn Entity> = Arc::new(entity);
+        self.entities
+            .entry(name)
+            .or_insert_with(|| Box::new(entity.clone()));
+        self.anonymous = Some((name, entity));
+
+        self
+    }
+
+    /// Subject of an unauthenticated request, evaluated against the registered principal
+    pub fn anonymous(&self) -> Result<EvaluateEntity<'static>, Error> {
+        let (name, entity) = self.anonymous.as_ref().ok_or(Error::AnonymousNotFound)?;
+
+        Ok(EvaluateEntity::shared(name, None, entity.clone()))
+    }
+
+    /// Relationship tuples used by `HasRelation` conditions
+    #[inline]
+    pub fn with_relations(mut self, relations: Relations) -> Self {
+        self.relations = Some(relations);
+
+        self
+    }
+
+    /// Role inheritance and permissions used by `HasRole`/`HasPermission` conditions
+    #[inline]
+    pub fn with_roles(mut self, roles: RoleGraph) -> Self {
+        self.roles = Some(roles);
+
+        self
+    }
+
+    /// Where `now()` and schedules read the current time from
+    #[inline]
+    pub fn with_clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Box::new(clock);
+
+        self
+    }
+
+    /// Named condition referenced from any rules with `Condition::Named`
+    #[inline]
+    pub fn define(mut self, name: impl Into<String>, rules: impl Into<Rules>) -> Self {
+        self.definitions = self.definitions.define(name, rules);
+        self.compiled = OnceLock::new();
 
         self
     }
 
+    /// Named conditions of a policy module, replacing the ones with the same name
+    #[inline]
+    pub fn with_definitions(mut self, definitions: Definitions) -> Self {
+        self.definitions.extend(definitions);
+        self.compiled = OnceLock::new();
+
+        self
+    }
+
+    /// Policy referenced from any rules with `Condition::Ref`
+    #[inline]
+    pub fn with_policy(mut self, id: impl Into<String>, rules: impl Into<Rules>) -> Self {
+        self.policies = self.policies.insert(id, rules);
+        self.compiled = OnceLock::new();
+
+        self
+    }
+
+    /// Stored policies, replacing the ones with the same id
+    #[inline]
+    pub fn with_policies(mut self, policies: Policies) -> Self {
+        self.policies.extend(policies);
+        self.compiled = OnceLock::new();
+
+        self
+    }
+
+    pub fn policy(&self, id: &str) -> Option<&Rules> {
+        self.policies.get(id)
+    }
+
+    /// Replace every named condition and policy reference by its rules, eg. to explain a decision
+    pub fn inline(&self, rules: &Rules) -> Result<Rules, Error> {
+        Inliner::new(&self.definitions, Some(&self.policies)).inline_rules(rules)
+    }
+
+    /// Every stored policy and definition must only reference existing ones, without cycles
+    ///
+    /// Meant to run once the policies are loaded, evaluating reports the same errors
+    pub fn check_policies(&self) -> Result<(), Error> {
+        self.compiled().map(|_| ())
+    }
+
+    /// Check the definitions and policies at startup rather than on the first evaluation
+    pub fn compile(self) -> Result<Self, Error> {
+        self.check_policies()?;
+
+        Ok(self)
+    }
+
+    /// Definitions and policies checked once, until another one is added
+    pub(crate) fn compiled(&self) -> Result<&Compiled, Error> {
+        if let Some(compiled) = self.compiled.get() {
+            return Ok(compiled);
+        }
+
+        let compiled = Compiled::new(&self.definitions, &self.policies)?;
+
+        Ok(self.compiled.get_or_init(|| compiled))
+    }
+
+    /// Does the subject have the relation on the object?
+    pub async fn check(
+        &self,
+        object: &ObjectRef,
+        relation: &str,
+        subject: &ObjectRef,
+    ) -> Result<bool, Error> {
+        self.relations
+            .as_ref()
+            .ok_or(Error::RelationsNotFound)?
+            .check(object, relation, subject)
+            .await
+    }
+
+    /// Check every relation the rules use, reading tuples is async unlike evaluating the rules
+    ///
+    /// Without relations on the engine nothing is checked, evaluating reports it
+    async fn check_relations(
+        &self,
+        ctx: &Context<'_>,
+        rules: Vec<&RelationRule>,
+    ) -> Result<Vec<RelationCheck>, Error> {
+        let Some(relations) = &self.relations else {
+            return Ok(Vec::new());
+        };
+
+        let mut checks = Vec::<RelationCheck>::new();
+        for rule in rules {
+            // Entity without identity can't hold any relation
+            let (Some(subject), Some(object)) = (
+                ctx.target_ref(&rule.subject)?,
+                ctx.target_ref(&rule.object)?,
+            ) else {
+                continue;
+            };
+            if checks
+                .iter()
+                .any(|check| check.is(object, &rule.relation, subject))
+            {
+                continue;
+            }
+
+            let granted = relations.check(object, &rule.relation, subject).await?;
+            checks.push(RelationCheck {
+                object: object.clone(),
+                relation: rule.relation.clone(),
+                subject: subject.clone(),
+                granted,
+            });
+        }
+
+        Ok(checks)
+    }
+
     #[inline]
     pub fn get_entity_fields(&self, name: &str) -> Result<&'static [&'static str], Error> {
         let entity = self.entities.get(name).ok_or(Error::AdapterNotFound)?;
@@ -64,35 +471,326 @@ impl Engine {
         Ok(entity.field_names())
     }
 
-    pub async fn load(&self, evaluate: EvaluateEntity<'_>) -> Result<Box<dyn Entity>, Error> {
+    /// Describe every registered entity along with its attribute types
+    pub fn schema(&self) -> Schema {
+        let mut entities = self
+            .entities
+            .iter()
+            .map(|(name, entity)| self.entity_schema(name, entity.as_ref()))
+            .collect::<Vec<_>>();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Schema { entities }
+    }
+
+    /// Entity's own attributes along with the resolved ones
+    pub(crate) fn entity_schema(&self, name: &str, entity: &dyn Entity) -> EntitySchema {
+        let mut attributes = entity.attributes();
+
+        for (attribute, resolver) in self.resolvers.get(name).into_iter().flatten() {
+            attributes.retain(|attr| attr.name != *attribute);
+            attributes.push(AttributeSchema::new(*attribute, resolver.attribute_type()));
+        }
+
+        EntitySchema {
+            name: name.to_string(),
+            attributes,
+        }
+    }
+
+    pub async fn load<'a>(
+        &self,
+        evaluate: EvaluateEntity<'a>,
+    ) -> Result<Box<dyn Entity + 'a>, Error> {
+        self.load_projected(evaluate, None).await
+    }
+
+    /// Load the entity, only with the given fields when there are some
+    ///
+    /// Preloaded entities are used as they are, without going through the adapter
+    pub(crate) async fn load_projected<'a>(
+        &self,
+        evaluate: EvaluateEntity<'a>,
+        fields: Option<&[&str]>,
+    ) -> Result<Box<dyn Entity + 'a>, Error> {
         let EvaluateEntity {
             name: rsc_name,
             id: rsc_id,
+            preloaded,
         } = evaluate;
 
+        match preloaded {
+            Some(Preloaded::Borrowed(entity)) => return Ok(Box::new(entity)),
+            Some(Preloaded::Shared(entity)) => return Ok(Box::new(entity)),
+            None => {}
+        }
+
         match rsc_id {
             Some(id) => {
                 let rsc_adapter = self.adapters.get(rsc_name).ok_or(Error::AdapterNotFound)?;
-                let rsc_provider = self
-                    .providers
-                    .get(&rsc_adapter.provider_type())
-                    .ok_or(Error::ProviderNotFound)?;
+                let rsc_provider =
+                    self.provider(rsc_adapter.provider_type(), rsc_adapter.provider_name())?;
+
+                // Nothing to project when only checking the entity exists, eg. a `SELECT` needs columns
+                let entity = match fields {
+                    Some(fields) if !fields.is_empty() => {
+                        rsc_adapter
+                            .load_fields(id.clone(), fields, rsc_provider)
+                            .await
+                    }
+                    _ => rsc_adapter.load(id.clone(), rsc_provider).await,
+                };
 
-                rsc_adapter.load(id, rsc_provider.as_ref()).await
+                // The adapter doesn't know which name it's registered as
+                entity.map_err(|err| match err {
+                    Error::NotFound => Error::EntityNotFound {
+                        name: rsc_name.to_string(),
+                        id,
+                    },
+                    err => err,
+                })
             }
             None => Ok(Box::new(EmptyEntity)),
         }
     }
 
+    /// Provider of the type registered as `name`, or by its type alone when there's no name
+    pub(crate) fn provider(
+        &self,
+        provider_type: TypeId,
+        name: Option<&'static str>,
+    ) -> Result<&(dyn Any + Send + Sync), Error> {
+        match self.providers.get(&(provider_type, name)) {
+            Some(provider) => Ok(provider.as_ref()),
+            None => Err(match name {
+                Some(name) => Error::NamedProviderNotFound(name.to_string()),
+                None => Error::ProviderNotFound,
+            }),
+        }
+    }
+
+    /// Fields the adapter must load for the paths, references are loaded through their id field
+    /// and resolved attributes through the fields their resolver reads
+    ///
+    /// None when the whole entity is needed, eg. by a resolver that doesn't declare its fields
+    pub(crate) fn projected_fields<'a>(
+        &'a self,
+        name: &str,
+        paths: &[&'a [PathSegment]],
+    ) -> Option<Vec<&'a str>> {
+        let references = self
+            .entities
+            .get(name)
+            .map(|entity| entity.references())
+            .unwrap_or_default();
+        let resolvers = self.resolvers.get(name);
+
+        let mut fields = Vec::new();
+        for path in paths {
+            let Some(PathSegment::Key(root)) = path.first() else {
+                continue;
+            };
+
+            let used = match references.iter().find(|r| r.alias == root) {
+                Some(reference) => vec![reference.field],
+                None => match resolvers.and_then(|r| r.get(root.as_str())) {
+                    Some(resolver) => resolver.fields()?.to_vec(),
+                    None => vec![root.as_str()],
+                },
+            };
+
+            for field in used {
+                if !fields.contains(&field) {
+                    fields.push(field);
+                }
+            }
+        }
+
+        Some(fields)
+    }
+
+    /// Evaluate the stored policy
+    pub async fn evaluate_policy(
+        &self,
+        subject: EvaluateEntity<'_>,
+        resource: EvaluateEntity<'_>,
+        id: &str,
+    ) -> Result<bool, Error> {
+        let rules = Rules::from(Condition::Ref(id.to_string()));
+
+        self.evaluate(subject, resource, &rules).await
+    }
+
     pub async fn evaluate(
         &self,
         subject: EvaluateEntity<'_>,
         resource: EvaluateEntity<'_>,
         rules: &Rules,
     ) -> Result<bool, Error> {
-        let subject_entity = self.load(subject).await?;
-        let resource_entity = self.load(resource).await?;
+        self.evaluate_request(EvaluateRequest::new(subject, resource), rules)
+            .await
+    }
+
+    /// Evaluate with the other entities of the request, referenced by their alias
+    pub async fn evaluate_request(
+        &self,
+        request: EvaluateRequest<'_>,
+        rules: &Rules,
+    ) -> Result<bool, Error> {
+        let decision = self.decide_request(request, rules).await?;
+
+        Ok(decision == Decision::Permit)
+    }
+
+    /// Like `evaluate`, telling a denial apart from a resource that doesn't exist
+    pub async fn decide(
+        &self,
+        subject: EvaluateEntity<'_>,
+        resource: EvaluateEntity<'_>,
+        rules: &Rules,
+    ) -> Result<Decision, Error> {
+        self.decide_request(EvaluateRequest::new(subject, resource), rules)
+            .await
+    }
+
+    pub async fn decide_request(
+        &self,
+        request: EvaluateRequest<'_>,
+        rules: &Rules,
+    ) -> Result<Decision, Error> {
+        let compiled = self.compiled()?;
+        let EvaluateRequest {
+            subject,
+            resource,
+            entities,
+        } = request;
+
+        // Without an id the subject would silently be an empty entity
+        if self.subject_required && subject.is_empty() {
+            return Err(Error::SubjectNotFound);
+        }
+
+        // Named conditions and policies are evaluated as they are, what they read gets loaded too
+        let mut rule_paths = rules.paths();
+        let mut rule_relations = rules.relations();
+        for reference in rules.references() {
+            let reads = compiled.reads(reference)?;
+
+            rule_paths.extend(
+                reads
+                    .paths
+                    .iter()
+                    .map(|(target, path)| (target.clone(), path)),
+            );
+            rule_relations.extend(&reads.relations);
+        }
+        let mut paths = rule_paths.into_iter().fold(
+            HashMap::<Target, Vec<_>>::new(),
+            |mut paths, (target, path)| {
+                paths.entry(target).or_default().push(path.segments());
+
+                paths
+            },
+        );
+        let mut target_paths = |target| paths.remove(&target).unwrap_or_default();
+
+        // Load the referenced entities and resolve the attributes the rules use, once per request
+        let mut loaded = HashMap::new();
+        let subject_ref = subject.object_ref();
+        let subject_entity = self
+            .load_linked(subject, target_paths(Target::Subject), false, &mut loaded)
+            .await?;
+        let resource_ref = resource.object_ref();
+        let (resource_name, resource_id) = (resource.name, resource.id.clone());
+        let resource_paths = target_paths(Target::Object);
+        // Loaded even when the rules read nothing from it, to know whether it exists.
+        // Only the resource itself missing is up to `missing_resource`, not its references
+        let resource_entity = match self.load_unlinked(resource, &resource_paths, true).await {
+            Err(err @ Error::EntityNotFound { .. }) => return self.missing_resource.decide(err),
+            result => result?,
+        };
+        let resource_entity = self
+            .link(
+                resource_name,
+                resource_id,
+                Arc::from(resource_entity),
+                resource_paths,
+                0,
+                &mut loaded,
+            )
+            .await?;
 
-        evaluate(subject_entity.as_ref(), resource_entity.as_ref(), rules)
+        let mut linked = Vec::with_capacity(entities.len());
+        for (alias, entity) in entities {
+            let object_ref = entity.object_ref();
+            let paths = target_paths(Target::Entity(alias.to_string()));
+            let entity = self.load_linked(entity, paths, false, &mut loaded).await?;
+
+            linked.push((alias, entity, object_ref));
+        }
+        let entities = linked
+            .iter()
+            .map(|(alias, entity, object_ref)| AliasedEntity {
+                alias,
+                entity,
+                object_ref: object_ref.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let ctx = Context {
+            subject: &subject_entity,
+            object: &resource_entity,
+            subject_ref,
+            object_ref: resource_ref,
+            entities: &entities,
+            now: self.clock.now(),
+            engine: Some(self),
+            element: None,
+            relations: &[],
+        };
+        let relations = self.check_relations(&ctx, rule_relations).await?;
+        let ctx = Context {
+            relations: &relations,
+            ..ctx
+        };
+
+        match evaluate_rules(&ctx, rules)? {
+            true => Ok(Decision::Permit),
+            false => Ok(Decision::Deny),
+        }
+    }
+
+    /// Load the entity with only the fields the paths use, then link it
+    async fn load_linked<'a, 'e: 'a>(
+        &'a self,
+        evaluate: EvaluateEntity<'e>,
+        paths: Vec<&'a [PathSegment]>,
+        required: bool,
+        loaded: &'a mut LoadedEntities,
+    ) -> Result<LinkedEntity<'e>, Error> {
+        let (name, id) = (evaluate.name, evaluate.id.clone());
+        let entity = self.load_unlinked(evaluate, &paths, required).await?;
+
+        self.link(name, id, Arc::from(entity), paths, 0, loaded)
+            .await
+    }
+
+    /// Load the entity with only the fields the paths use, without its references
+    ///
+    /// An entity nothing is read from isn't loaded at all, unless `required` to know it exists
+    async fn load_unlinked<'e>(
+        &self,
+        evaluate: EvaluateEntity<'e>,
+        paths: &[&[PathSegment]],
+        required: bool,
+    ) -> Result<Box<dyn Entity + 'e>, Error> {
+        let fields = self.projected_fields(evaluate.name, paths);
+
+        match &fields {
+            Some(fields) if fields.is_empty() && evaluate.preloaded.is_none() && !required => {
+                Ok(Box::new(EmptyEntity))
+            }
+            fields => self.load_projected(evaluate, fields.as_deref()).await,
+        }
     }
 }
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
 use serde_value::Value;
 
-use crate::Error;
+use crate::{AttributeSchema, AttributeType, Error, Reference};
 
 pub(crate) type EntityValue = HashMap<String, Value>;
 
@@ -13,6 +13,107 @@ pub trait Entity: Send + Sync {
 
     /// Getting the field name
     fn field_names(&self) -> &'static [&'static str];
+
+    /// Getting a single attribute without building the whole map, None when there's no such attribute
+    ///
+    /// Falls back to `to_value`, the derive generates a cheaper one
+    fn get_attribute(&self, name: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        Ok(self.to_value()?.remove(name).map(Cow::Owned))
+    }
+
+    /// Getting the attributes along with their type
+    ///
+    /// Falls back to `field_names` with unknown types, the derive generates the real ones
+    fn attributes(&self) -> Vec<AttributeSchema> {
+        self.field_names()
+            .iter()
+            .map(|name| AttributeSchema::new(*name, AttributeType::Any))
+            .collect()
+    }
+
+    /// Getting the type of a single attribute, None when there's no such attribute
+    ///
+    /// Falls back to `attributes`, the derive generates a cheaper one
+    fn attribute_type(&self, name: &str) -> Option<AttributeType> {
+        self.attributes()
+            .into_iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.ty)
+    }
+
+    /// Attributes referencing other registered entities
+    fn references(&self) -> &'static [Reference] {
+        &[]
+    }
+}
+
+/// Borrowed entity, eg. a preloaded one
+impl<E: Entity + ?Sized> Entity for &E {
+    fn to_value(&self) -> Result<EntityValue, Error> {
+        (**self).to_value()
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        (**self).field_names()
+    }
+
+    fn get_attribute(&self, name: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        (**self).get_attribute(name)
+    }
+
+    fn attributes(&self) -> Vec<AttributeSchema> {
+        (**self).attributes()
+    }
+
+    fn attribute_type(&self, name: &str) -> Option<AttributeType> {
+        (**self).attribute_type(name)
+    }
+
+    fn references(&self) -> &'static [Reference] {
+        (**self).references()
+    }
+}
+
+/// Shared entity, eg. a preloaded one
+impl<E: Entity + ?Sized> Entity for Arc<E> {
+    fn to_value(&self) -> Result<EntityValue, Error> {
+        (**self).to_value()
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        (**self).field_names()
+    }
+
+    fn get_attribute(&self, name: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        (**self).get_attribute(name)
+    }
+
+    fn attributes(&self) -> Vec<AttributeSchema> {
+        (**self).attributes()
+    }
+
+    fn attribute_type(&self, name: &str) -> Option<AttributeType> {
+        (**self).attribute_type(name)
+    }
+
+    fn references(&self) -> &'static [Reference] {
+        (**self).references()
+    }
+}
+
+/// Already serialized entity, handy when the attributes don't come from a struct
+impl Entity for EntityValue {
+    fn to_value(&self) -> Result<EntityValue, Error> {
+        Ok(self.clone())
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn get_attribute(&self, name: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        Ok(self.get(name).map(Cow::Borrowed))
+    }
 }
 
 /// Empty Entity, this is used for something when resource is not needed to be evaluated (eg. List all Resources, Create Resource)
@@ -26,4 +127,8 @@ impl Entity for EmptyEntity {
     fn field_names(&self) -> &'static [&'static str] {
         &[]
     }
+
+    fn get_attribute(&self, _: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        Ok(None)
+    }
 }
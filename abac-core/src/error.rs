@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::EntityId;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Serialization error: {0}")]
@@ -11,9 +13,96 @@ pub enum Error {
     #[error("Provider not found!")]
     ProviderNotFound,
 
+    #[error("Provider `{0}` not found!")]
+    NamedProviderNotFound(String),
+
+    /// Returned by an adapter when there is no entity with the id, telling it apart from a failure
+    ///
+    /// The engine reports it as `EntityNotFound` along with the registered name and the id
+    #[error("Entity doesn't exist")]
+    NotFound,
+
+    #[error("Entity `{name}` with id `{id}` doesn't exist")]
+    EntityNotFound { name: String, id: EntityId },
+
     #[error("Subject shouldn't be None!")]
     SubjectNotFound,
 
+    #[error("Anonymous principal isn't registered!")]
+    AnonymousNotFound,
+
+    #[error("Invalid id `{id}`, expected {expected}")]
+    InvalidEntityId {
+        id: EntityId,
+        expected: &'static str,
+    },
+
+    #[error("Invalid attribute path: {0}")]
+    InvalidPath(String),
+
+    #[error("Attribute `{path}` doesn't exist on entity `{entity}`")]
+    UnknownAttribute { entity: String, path: String },
+
+    #[error("Reference traversal is deeper than {0}")]
+    MaxDepthExceeded(usize),
+
+    #[error("Relation check is deeper than {0}")]
+    RelationDepthExceeded(usize),
+
+    #[error("Invalid relation tuple: {0}")]
+    InvalidTuple(String),
+
+    #[error("Relations aren't configured on the engine!")]
+    RelationsNotFound,
+
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+
+    #[error("Invalid arguments for `{function}`: {reason}")]
+    InvalidArguments { function: String, reason: String },
+
+    #[error("Invalid operands for `{operator}`: {reason}")]
+    InvalidOperands { operator: String, reason: String },
+
+    #[error("Invalid duration: {0}")]
+    InvalidDuration(String),
+
+    #[error("Invalid CIDR block: {0}")]
+    InvalidCidr(String),
+
+    #[error("Arithmetic overflow in `{0}`")]
+    ArithmeticOverflow(String),
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("Element is only bound inside `Any`/`All`")]
+    UnboundElement,
+
+    #[error("Unknown named condition: {0}")]
+    UnknownCondition(String),
+
+    #[error("Named conditions reference each other: {0}")]
+    ConditionCycle(String),
+
+    #[error("Unknown policy: {0}")]
+    UnknownPolicy(String),
+
+    #[error("Policies reference each other: {0}")]
+    PolicyCycle(String),
+
+    #[error("No entity `{0}` in the request")]
+    UnknownAlias(String),
+
+    #[error("Template parameter `{0}` isn't bound")]
+    UnboundParameter(String),
+
+    #[error("Template has no parameter `{0}`")]
+    UnknownParameter(String),
+
+    #[error("Invalid value for template parameter `{name}`: {reason}")]
+    InvalidParameter { name: String, reason: String },
+
     /// This error should be provided by user when impl to load data
     /// Error should be able to be stringified
     #[error("Something wrong when loading entity: {0}")]
@@ -21,7 +110,13 @@ pub enum Error {
 }
 
 impl Error {
+    /// Errors of this crate are kept as they are, eg. `NotFound`
     pub fn load_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> Self {
-        Self::LoadError(Box::new(err))
+        let err: Box<dyn std::error::Error + Send + Sync> = Box::new(err);
+
+        match err.downcast::<Self>() {
+            Ok(err) => *err,
+            Err(err) => Self::LoadError(err),
+        }
     }
 }
@@ -1,50 +1,477 @@
+use std::{borrow::Cow, cmp::Ordering, net::IpAddr, sync::LazyLock};
+
+use chrono::{DateTime, TimeDelta, Utc};
 use serde_value::Value;
 
-use crate::{Entity, EntityValue, Error, Operator, Rules, SideRule};
+use crate::{
+    AttrPath, AttributeType, Builtin, Cidr, Condition, CustomFunction, Engine, Entity, Error,
+    FunctionCall, ObjectRef, Operator, PathSegment, PermissionRule, Quantifier, RelationCheck,
+    RelationRule, RoleGraph, RoleRule, Rule, Rules, Schedule, SideRule, Target, as_cidrs, as_ip,
+    as_timestamp, held_roles, numeric_cmp, resolve_value, temporal_cmp, unwrap_optional,
+    unwrap_value,
+};
+
+/// Entity referenced by its alias from the rules, eg. `entity("tenant", Tenant::PLAN)`
+pub(crate) struct AliasedEntity<'a> {
+    pub(crate) alias: &'a str,
+    pub(crate) entity: &'a dyn Entity,
+    /// Identity in the relation graph, None when not loaded by id
+    pub(crate) object_ref: Option<ObjectRef>,
+}
+
+/// Everything a rule may look at while being evaluated
+#[derive(Clone)]
+pub(crate) struct Context<'a> {
+    pub(crate) subject: &'a dyn Entity,
+    pub(crate) object: &'a dyn Entity,
+    /// Identity of the subject/object in the relation graph, None when not loaded by id
+    pub(crate) subject_ref: Option<ObjectRef>,
+    pub(crate) object_ref: Option<ObjectRef>,
+    /// Other entities of the request
+    pub(crate) entities: &'a [AliasedEntity<'a>],
+    /// Current time, read once so every rule of the request sees the same one
+    pub(crate) now: DateTime<Utc>,
+    /// Engine's registries, None when evaluating without an engine
+    pub(crate) engine: Option<&'a Engine>,
+    /// Element bound by the enclosing `Any`/`All`
+    pub(crate) element: Option<&'a Value>,
+    /// Relations of the rules, checked by the engine beforehand
+    pub(crate) relations: &'a [RelationCheck],
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(subject: &'a dyn Entity, object: &'a dyn Entity) -> Self {
+        Self {
+            subject,
+            object,
+            subject_ref: None,
+            object_ref: None,
+            entities: &[],
+            now: Utc::now(),
+            engine: None,
+            element: None,
+            relations: &[],
+        }
+    }
+
+    /// Role graph of the engine, roles are flat without one
+    fn roles(&self) -> &RoleGraph {
+        static FLAT: LazyLock<RoleGraph> = LazyLock::new(RoleGraph::new);
+
+        self.engine
+            .and_then(|engine| engine.roles.as_ref())
+            .unwrap_or(&FLAT)
+    }
+
+    /// Function registered on the engine
+    fn function(&self, name: &str) -> Option<&dyn CustomFunction> {
+        self.engine
+            .and_then(|engine| engine.functions.get(name))
+            .map(AsRef::as_ref)
+    }
+
+    /// Named condition, only the engine knows the definitions
+    fn definition(&self, name: &str) -> Result<&'a Rules, Error> {
+        self.engine
+            .and_then(|engine| engine.definitions.get(name))
+            .ok_or_else(|| Error::UnknownCondition(name.to_string()))
+    }
+
+    /// Stored policy, only the engine knows the policies
+    fn policy(&self, id: &str) -> Result<&'a Rules, Error> {
+        self.engine
+            .and_then(|engine| engine.policies.get(id))
+            .ok_or_else(|| Error::UnknownPolicy(id.to_string()))
+    }
+
+    /// Entity of the request referenced as `alias`
+    fn entity(&self, alias: &str) -> Result<&'a AliasedEntity<'a>, Error> {
+        self.entities
+            .iter()
+            .find(|entity| entity.alias == alias)
+            .ok_or_else(|| Error::UnknownAlias(alias.to_string()))
+    }
+
+    pub(crate) fn target_ref(&self, target: &Target) -> Result<Option<&ObjectRef>, Error> {
+        let object_ref = match target {
+            Target::Subject => self.subject_ref.as_ref(),
+            Target::Object => self.object_ref.as_ref(),
+            Target::Entity(alias) => self.entity(alias)?.object_ref.as_ref(),
+        };
+
+        Ok(object_ref)
+    }
+}
 
 /// Which to evaluate based on the left/right rule
+///
+/// Attributes are looked up on demand, so only the fields used by the rule get serialized
 pub(crate) fn which_to_evaluate<'a>(
-    subject: &'a EntityValue,
-    object: &'a EntityValue,
+    ctx: &Context<'a>,
+    side_rule: &'a SideRule,
+) -> Result<Cow<'a, Value>, Error> {
+    let value = resolve_side(ctx, side_rule)?.unwrap_or(Cow::Owned(Value::Bool(false)));
+
+    Ok(value)
+}
+
+/// Value of one side, None when the attribute doesn't exist
+fn resolve_side<'a>(
+    ctx: &Context<'a>,
     side_rule: &'a SideRule,
-) -> &'a Value {
-    match side_rule {
-        SideRule::Subject(field_name) => subject.get(field_name).unwrap_or(&Value::Bool(false)),
-        SideRule::Object(field_name) => object.get(field_name).unwrap_or(&Value::Bool(false)),
-        SideRule::Literal(value) => value,
+) -> Result<Option<Cow<'a, Value>>, Error> {
+    let value = match side_rule {
+        SideRule::Subject(path) => return path.resolve(ctx.subject),
+        SideRule::Object(path) => return path.resolve(ctx.object),
+        SideRule::Entity(alias, path) => return path.resolve(ctx.entity(alias)?.entity),
+        SideRule::Element(path) => {
+            let element = Cow::Borrowed(ctx.element.ok_or(Error::UnboundElement)?);
+
+            return Ok(match path {
+                Some(path) => resolve_value(element, path.segments()),
+                None => Some(element),
+            });
+        }
+        SideRule::Literal(value) => Cow::Borrowed(value),
+        SideRule::Function(call) => Cow::Owned(call_function(ctx, call)?),
+        SideRule::Arithmetic(expression) => {
+            let left = which_to_evaluate(ctx, &expression.left)?;
+            let right = which_to_evaluate(ctx, &expression.right)?;
+
+            Cow::Owned(expression.operator.apply(&left, &right)?)
+        }
+        SideRule::Param(name) => return Err(Error::UnboundParameter(name.clone())),
+        SideRule::Cidr(blocks) => Cow::Owned(Value::Seq(
+            blocks
+                .iter()
+                .map(|block| Value::String(block.to_string()))
+                .collect(),
+        )),
+    };
+
+    Ok(Some(value))
+}
+
+fn call_function(ctx: &Context<'_>, call: &FunctionCall) -> Result<Value, Error> {
+    let args = call
+        .args
+        .iter()
+        .map(|arg| which_to_evaluate(ctx, arg).map(Cow::into_owned))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(function) = ctx.function(&call.name) {
+        return call_custom(function, &args);
     }
+
+    let builtin =
+        Builtin::from_name(&call.name).ok_or_else(|| Error::UnknownFunction(call.name.clone()))?;
+
+    builtin
+        .call(args, ctx.now)
+        .map_err(|reason| Error::InvalidArguments {
+            function: call.name.clone(),
+            reason,
+        })
 }
 
-/// The actual
-pub fn evaluate(subject: &dyn Entity, object: &dyn Entity, rules: &Rules) -> Result<bool, Error> {
-    // Construct Entity
-    let subject = subject.to_value()?;
-    let object = object.to_value()?;
+fn call_custom(function: &dyn CustomFunction, args: &[Value]) -> Result<Value, Error> {
+    if args.len() != function.arity() {
+        return Err(Error::InvalidArguments {
+            function: function.name().to_string(),
+            reason: format!(
+                "expects {} argument(s), got {}",
+                function.arity(),
+                args.len()
+            ),
+        });
+    }
+
+    function.call(args)
+}
+
+/// Custom operator, the function must return a bool
+fn call_operator(ctx: &Context<'_>, name: &str, left: Value, right: Value) -> Result<bool, Error> {
+    let function = ctx
+        .function(name)
+        .ok_or_else(|| Error::UnknownFunction(name.to_string()))?;
+
+    match call_custom(function, &[left, right])? {
+        Value::Bool(pass) => Ok(pass),
+        other => Err(Error::InvalidArguments {
+            function: name.to_string(),
+            reason: format!("operator should return a bool, got {other:?}"),
+        }),
+    }
+}
+
+fn evaluate_compare(ctx: &Context<'_>, rule: &Rule) -> Result<bool, Error> {
+    if let Operator::InCidr | Operator::NotInCidr = rule.operator {
+        return evaluate_cidr(ctx, rule);
+    }
+
+    let left = which_to_evaluate(ctx, &rule.left)?;
+    let right = which_to_evaluate(ctx, &rule.right)?;
+
+    // Temporal attributes compare chronologically, other strings as they are
+    let temporal = match (unwrap_value(&left), unwrap_value(&right)) {
+        (Value::String(_), Value::String(_)) => temporal_type(ctx, rule),
+        _ => None,
+    };
+
+    // Numbers compare by value, eg. `U64(5) == I64(5)`
+    let ordering = match temporal {
+        Some(ty) => temporal_cmp(&ty, &left, &right),
+        None => numeric_cmp(&left, &right),
+    };
+    if let Some(ordering) = ordering {
+        let pass = match rule.operator {
+            Operator::Equal => ordering == Ordering::Equal,
+            Operator::Greater => ordering == Ordering::Greater,
+            Operator::Less => ordering == Ordering::Less,
+            Operator::GreaterEqual => ordering != Ordering::Less,
+            Operator::LessEqual => ordering != Ordering::Greater,
+            _ => compare_values(ctx, &rule.operator, left, right)?,
+        };
+
+        return Ok(pass);
+    }
+
+    compare_values(ctx, &rule.operator, left, right)
+}
+
+/// Timestamp, date or duration type of either side, from the schema of the entity it's read from
+fn temporal_type(ctx: &Context<'_>, rule: &Rule) -> Option<AttributeType> {
+    [&rule.left, &rule.right].into_iter().find_map(|side| {
+        let ty = match side {
+            SideRule::Subject(path) => attribute_type(ctx.subject, path),
+            SideRule::Object(path) => attribute_type(ctx.object, path),
+            SideRule::Entity(alias, path) => attribute_type(ctx.entity(alias).ok()?.entity, path),
+            SideRule::Function(call) if call.name == "now" && ctx.function("now").is_none() => {
+                Some(AttributeType::Timestamp)
+            }
+            _ => None,
+        }?;
+
+        match unwrap_optional(&ty) {
+            ty @ (AttributeType::Timestamp | AttributeType::Date | AttributeType::Duration) => {
+                Some(ty.clone())
+            }
+            _ => None,
+        }
+    })
+}
+
+fn attribute_type(entity: &dyn Entity, path: &AttrPath) -> Option<AttributeType> {
+    let (PathSegment::Key(root), rest) = path.segments().split_first()? else {
+        return None;
+    };
 
+    entity.attribute_type(root)?.segments_type(rest)
+}
+
+fn compare_values(
+    ctx: &Context<'_>,
+    operator: &Operator,
+    left: Cow<'_, Value>,
+    right: Cow<'_, Value>,
+) -> Result<bool, Error> {
+    let pass = match operator {
+        Operator::Equal => left == right,
+        Operator::Greater => left > right,
+        Operator::Less => left < right,
+        Operator::GreaterEqual => left >= right,
+        Operator::LessEqual => left <= right,
+        Operator::Before => {
+            let (left, right) = timestamps(operator, &left, &right)?;
+
+            left < right
+        }
+        Operator::After => {
+            let (left, right) = timestamps(operator, &left, &right)?;
+
+            left > right
+        }
+        Operator::Within(duration) => {
+            let (left, right) = timestamps(operator, &left, &right)?;
+
+            (left - right).abs() <= TimeDelta::from(*duration)
+        }
+        Operator::InCidr | Operator::NotInCidr => evaluate_cidr_values(operator, &left, &right)?,
+        Operator::Custom(name) => call_operator(ctx, name, left.into_owned(), right.into_owned())?,
+    };
+
+    Ok(pass)
+}
+
+/// IP address against CIDR blocks, literal blocks were already parsed with the rules
+fn evaluate_cidr(ctx: &Context<'_>, rule: &Rule) -> Result<bool, Error> {
+    let left = which_to_evaluate(ctx, &rule.left)?;
+
+    match &rule.right {
+        SideRule::Cidr(blocks) => {
+            let addr = as_ip(&left).map_err(|reason| invalid_operands(&rule.operator, reason))?;
+
+            Ok(cidr_match(&rule.operator, &addr, blocks))
+        }
+        right => {
+            let right = which_to_evaluate(ctx, right)?;
+
+            evaluate_cidr_values(&rule.operator, &left, &right)
+        }
+    }
+}
+
+fn evaluate_cidr_values(operator: &Operator, left: &Value, right: &Value) -> Result<bool, Error> {
+    let invalid = |reason| invalid_operands(operator, reason);
+    let addr = as_ip(left).map_err(invalid)?;
+    let blocks = as_cidrs(right).map_err(invalid)?;
+
+    Ok(cidr_match(operator, &addr, &blocks))
+}
+
+fn cidr_match(operator: &Operator, addr: &IpAddr, blocks: &[Cidr]) -> bool {
+    let inside = blocks.iter().any(|block| block.contains(addr));
+
+    match operator {
+        Operator::NotInCidr => !inside,
+        _ => inside,
+    }
+}
+
+fn invalid_operands(operator: &Operator, reason: String) -> Error {
+    Error::InvalidOperands {
+        operator: format!("{operator:?}"),
+        reason,
+    }
+}
+
+/// Both sides of a temporal operator
+fn timestamps(
+    operator: &Operator,
+    left: &Value,
+    right: &Value,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+    let timestamp =
+        |value| as_timestamp(value).map_err(|reason| invalid_operands(operator, reason));
+
+    Ok((timestamp(left)?, timestamp(right)?))
+}
+
+/// Nested rules against each element, `all` requires every element to pass
+fn evaluate_quantifier(
+    ctx: &Context<'_>,
+    quantifier: &Quantifier,
+    all: bool,
+) -> Result<bool, Error> {
+    let operator = if all { "All" } else { "Any" };
+    let collection = resolve_side(ctx, &quantifier.collection)?;
+    let elements = match collection.as_deref().map(unwrap_value) {
+        Some(Value::Seq(elements)) => elements.as_slice(),
+        // Missing collection has no elements
+        None | Some(Value::Unit | Value::Option(None)) => &[],
+        Some(other) => {
+            return Err(Error::InvalidOperands {
+                operator: operator.to_string(),
+                reason: format!("{other:?} isn't a list"),
+            });
+        }
+    };
+
+    for element in elements {
+        let ctx = Context {
+            element: Some(element),
+            ..ctx.clone()
+        };
+
+        if evaluate_rules(&ctx, &quantifier.rules)? != all {
+            return Ok(!all);
+        }
+    }
+
+    Ok(all)
+}
+
+fn evaluate_schedule(ctx: &Context<'_>, schedule: &Schedule) -> bool {
+    schedule.contains(ctx.now)
+}
+
+fn evaluate_relation(ctx: &Context<'_>, rule: &RelationRule) -> Result<bool, Error> {
+    if ctx
+        .engine
+        .and_then(|engine| engine.relations.as_ref())
+        .is_none()
+    {
+        return Err(Error::RelationsNotFound);
+    }
+
+    // Entity without identity can't hold any relation
+    let (Some(subject), Some(object)) = (
+        ctx.target_ref(&rule.subject)?,
+        ctx.target_ref(&rule.object)?,
+    ) else {
+        return Ok(false);
+    };
+
+    Ok(ctx
+        .relations
+        .iter()
+        .find(|check| check.is(object, &rule.relation, subject))
+        .is_some_and(|check| check.granted))
+}
+
+fn evaluate_role(ctx: &Context<'_>, rule: &RoleRule) -> Result<bool, Error> {
+    let Some(value) = rule.attribute.resolve(ctx.subject)? else {
+        return Ok(false);
+    };
+    let roles = ctx.roles();
+
+    Ok(roles.has_role(held_roles(&value), &rule.role))
+}
+
+fn evaluate_permission(ctx: &Context<'_>, rule: &PermissionRule) -> Result<bool, Error> {
+    let Some(value) = rule.attribute.resolve(ctx.subject)? else {
+        return Ok(false);
+    };
+    let roles = ctx.roles();
+
+    Ok(roles.has_permission(held_roles(&value), &rule.permission))
+}
+
+fn evaluate_condition(ctx: &Context<'_>, condition: &Condition) -> Result<bool, Error> {
+    match condition {
+        Condition::Compare(rule) => evaluate_compare(ctx, rule),
+        Condition::HasRelation(rule) => evaluate_relation(ctx, rule),
+        Condition::HasRole(rule) => evaluate_role(ctx, rule),
+        Condition::HasPermission(rule) => evaluate_permission(ctx, rule),
+        Condition::During(schedule) => Ok(evaluate_schedule(ctx, schedule)),
+        Condition::Any(quantifier) => evaluate_quantifier(ctx, quantifier, false),
+        Condition::All(quantifier) => evaluate_quantifier(ctx, quantifier, true),
+        Condition::Named(name) => evaluate_rules(ctx, ctx.definition(name)?),
+        Condition::Ref(id) => evaluate_rules(ctx, ctx.policy(id)?),
+        Condition::Inlined(definition) => evaluate_rules(ctx, &definition.rules),
+        Condition::Included(policy) => evaluate_rules(ctx, &policy.rules),
+    }
+}
+
+pub(crate) fn evaluate_rules(ctx: &Context<'_>, rules: &Rules) -> Result<bool, Error> {
     rules.0.iter().try_fold(true, |acc, r_and| {
         if !acc {
             return Ok(false); // short-circuit outer AND
         }
 
-        let or_result = r_and.iter().try_fold(false, |acc, rule| {
+        let or_result = r_and.iter().try_fold(false, |acc, condition| {
             if acc {
                 return Ok::<_, Error>(true); // short-circuit inner OR
             }
 
-            let left = which_to_evaluate(&subject, &object, &rule.left);
-            let right = which_to_evaluate(&subject, &object, &rule.right);
-
-            let pass = match rule.operator {
-                Operator::Equal => left == right,
-                Operator::Greater => left > right,
-                Operator::Less => left < right,
-                Operator::GreaterEqual => left >= right,
-                Operator::LessEqual => left <= right,
-            };
-
-            Ok(pass)
+            evaluate_condition(ctx, condition)
         })?;
 
         Ok(or_result)
     })
 }
+
+/// The actual
+pub fn evaluate(subject: &dyn Entity, object: &dyn Entity, rules: &Rules) -> Result<bool, Error> {
+    evaluate_rules(&Context::new(subject, object), rules)
+}
@@ -0,0 +1,453 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+
+use crate::{AttributeType, Error, SideRule, as_timestamp};
+
+/// Function call on either side of a rule, eg. `{ "Function": { "name": "lower", "args": [{ "Subject": "name" }] } }`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FunctionCall {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) args: Vec<SideRule>,
+}
+
+/// Function registered on the `Engine` and called by name from the rules, eg. `"clearance_level"`
+///
+/// Taking 2 arguments and returning a bool, it can also be used as a custom operator
+/// (`{ "Custom": "dominates" }`) with the left and right sides as arguments
+pub trait CustomFunction: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn arity(&self) -> usize;
+
+    /// Type of each argument, checked when validating the rules
+    fn parameters(&self) -> Vec<AttributeType> {
+        vec![AttributeType::Any; self.arity()]
+    }
+
+    fn return_type(&self) -> AttributeType {
+        AttributeType::Any
+    }
+
+    fn call(&self, args: &[Value]) -> Result<Value, Error>;
+}
+
+/// Type of the custom function's result for the given argument types, the reason when they don't fit
+pub(crate) fn check_custom(
+    function: &dyn CustomFunction,
+    args: &[AttributeType],
+) -> Result<AttributeType, String> {
+    if args.len() != function.arity() {
+        return Err(format!(
+            "expects {} argument(s), got {}",
+            function.arity(),
+            args.len()
+        ));
+    }
+
+    for (parameter, arg) in function.parameters().iter().zip(args) {
+        if !fits(parameter, arg) {
+            return Err(format!("expects {parameter:?}, got {arg:?}"));
+        }
+    }
+
+    Ok(function.return_type())
+}
+
+/// Can a value of type `actual` be passed where `expected` is expected? Unknown types always fit
+pub(crate) fn fits(expected: &AttributeType, actual: &AttributeType) -> bool {
+    use AttributeType as T;
+
+    match (unwrap_optional(expected), unwrap_optional(actual)) {
+        (T::Any, _) | (_, T::Any) => true,
+        // Temporal and network values are serialized as strings
+        (T::Timestamp | T::Date | T::Duration | T::IpAddress | T::Cidr, T::String)
+        | (T::String, T::Timestamp | T::Date | T::Duration | T::IpAddress | T::Cidr) => true,
+        (T::List(expected), T::List(actual)) | (T::Map(expected), T::Map(actual)) => {
+            fits(expected, actual)
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+/// Functions available to every rule
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Builtin {
+    Len,
+    Lower,
+    Upper,
+    Trim,
+    Abs,
+    Min,
+    Max,
+    Now,
+    /// Seconds from the second timestamp to the first one
+    DateDiff,
+    DayOfWeek,
+    Hour,
+    Split,
+    Concat,
+}
+
+impl Builtin {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let builtin = match name {
+            "len" => Builtin::Len,
+            "lower" => Builtin::Lower,
+            "upper" => Builtin::Upper,
+            "trim" => Builtin::Trim,
+            "abs" => Builtin::Abs,
+            "min" => Builtin::Min,
+            "max" => Builtin::Max,
+            "now" => Builtin::Now,
+            "date_diff" => Builtin::DateDiff,
+            "day_of_week" => Builtin::DayOfWeek,
+            "hour" => Builtin::Hour,
+            "split" => Builtin::Split,
+            "concat" => Builtin::Concat,
+            _ => return None,
+        };
+
+        Some(builtin)
+    }
+
+    /// Type of the result for the given argument types, the reason when they don't fit
+    pub(crate) fn check(&self, args: &[AttributeType]) -> Result<AttributeType, String> {
+        use AttributeType as T;
+
+        let arity = |expected: usize| match args.len() == expected {
+            true => Ok(()),
+            false => Err(format!(
+                "expects {expected} argument(s), got {}",
+                args.len()
+            )),
+        };
+
+        match self {
+            Builtin::Len => {
+                arity(1)?;
+                expect(&args[0], "a string, list or map", |ty| {
+                    matches!(ty, T::String | T::List(_) | T::Map(_))
+                })?;
+
+                Ok(T::Integer)
+            }
+            Builtin::Lower | Builtin::Upper | Builtin::Trim => {
+                arity(1)?;
+                expect(&args[0], "a string", |ty| matches!(ty, T::String))?;
+
+                Ok(T::String)
+            }
+            Builtin::Abs => {
+                arity(1)?;
+                expect(&args[0], "a number", is_number)?;
+
+                Ok(unwrap_optional(&args[0]).clone())
+            }
+            Builtin::Min | Builtin::Max => {
+                if args.is_empty() {
+                    return Err("expects at least 1 argument".into());
+                }
+                args.iter()
+                    .try_for_each(|arg| expect(arg, "a number", is_number))?;
+
+                // Unknown arguments may be floats
+                Ok(
+                    match args.iter().all(|arg| *unwrap_optional(arg) == T::Integer) {
+                        true => T::Integer,
+                        false => T::Float,
+                    },
+                )
+            }
+            Builtin::Now => {
+                arity(0)?;
+
+                Ok(T::Timestamp)
+            }
+            Builtin::DateDiff => {
+                arity(2)?;
+                args.iter()
+                    .try_for_each(|arg| expect(arg, "a timestamp", is_timestamp))?;
+
+                Ok(T::Integer)
+            }
+            Builtin::DayOfWeek => {
+                arity(1)?;
+                expect(&args[0], "a timestamp", is_timestamp)?;
+
+                Ok(T::String)
+            }
+            Builtin::Hour => {
+                arity(1)?;
+                expect(&args[0], "a timestamp", is_timestamp)?;
+
+                Ok(T::Integer)
+            }
+            Builtin::Split => {
+                arity(2)?;
+                args.iter()
+                    .try_for_each(|arg| expect(arg, "a string", |ty| matches!(ty, T::String)))?;
+
+                Ok(T::List(Box::new(T::String)))
+            }
+            Builtin::Concat => {
+                if args.is_empty() {
+                    return Err("expects at least 1 argument".into());
+                }
+
+                match args
+                    .iter()
+                    .any(|arg| matches!(unwrap_optional(arg), T::List(_)))
+                {
+                    true => {
+                        args.iter().try_for_each(|arg| {
+                            expect(arg, "a list", |ty| matches!(ty, T::List(_)))
+                        })?;
+
+                        Ok(T::List(Box::new(T::Any)))
+                    }
+                    false => {
+                        args.iter().try_for_each(|arg| {
+                            expect(arg, "a string", |ty| matches!(ty, T::String))
+                        })?;
+
+                        Ok(T::String)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Call the function, the reason when the arguments don't fit
+    pub(crate) fn call(&self, args: Vec<Value>, now: DateTime<Utc>) -> Result<Value, String> {
+        let arity = |expected: usize| match args.len() == expected {
+            true => Ok(()),
+            false => Err(format!(
+                "expects {expected} argument(s), got {}",
+                args.len()
+            )),
+        };
+
+        match self {
+            Builtin::Len => {
+                arity(1)?;
+
+                let len = match unwrap_value(&args[0]) {
+                    Value::String(s) => s.chars().count(),
+                    Value::Seq(seq) => seq.len(),
+                    Value::Map(map) => map.len(),
+                    other => return Err(format!("can't get length of {other:?}")),
+                };
+
+                Ok(Value::U64(len as u64))
+            }
+            Builtin::Lower | Builtin::Upper | Builtin::Trim => {
+                arity(1)?;
+
+                let s = as_str(&args[0])?;
+                let s = match self {
+                    Builtin::Lower => s.to_lowercase(),
+                    Builtin::Upper => s.to_uppercase(),
+                    _ => s.trim().to_string(),
+                };
+
+                Ok(Value::String(s))
+            }
+            Builtin::Abs => {
+                arity(1)?;
+
+                let overflow = || "overflow".to_string();
+                let value = match unwrap_value(&args[0]) {
+                    Value::I8(n) => Value::I8(n.checked_abs().ok_or_else(overflow)?),
+                    Value::I16(n) => Value::I16(n.checked_abs().ok_or_else(overflow)?),
+                    Value::I32(n) => Value::I32(n.checked_abs().ok_or_else(overflow)?),
+                    Value::I64(n) => Value::I64(n.checked_abs().ok_or_else(overflow)?),
+                    Value::F32(n) => Value::F32(n.abs()),
+                    Value::F64(n) => Value::F64(n.abs()),
+                    value @ (Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_)) => {
+                        value.clone()
+                    }
+                    other => return Err(format!("{other:?} isn't a number")),
+                };
+
+                Ok(value)
+            }
+            Builtin::Min | Builtin::Max => {
+                let mut args = args.into_iter();
+                let first = args.next().ok_or("expects at least 1 argument")?;
+
+                args.try_fold(first, |best, arg| {
+                    let ordering = compare_numbers(&arg, &best)?;
+                    let better = match self {
+                        Builtin::Min => ordering == Ordering::Less,
+                        _ => ordering == Ordering::Greater,
+                    };
+
+                    Ok(if better { arg } else { best })
+                })
+            }
+            Builtin::Now => {
+                arity(0)?;
+
+                Ok(Value::String(now.to_rfc3339()))
+            }
+            Builtin::DateDiff => {
+                arity(2)?;
+
+                let (a, b) = (as_timestamp(&args[0])?, as_timestamp(&args[1])?);
+
+                Ok(Value::I64((a - b).num_seconds()))
+            }
+            Builtin::DayOfWeek => {
+                arity(1)?;
+
+                Ok(Value::String(as_timestamp(&args[0])?.weekday().to_string()))
+            }
+            Builtin::Hour => {
+                arity(1)?;
+
+                Ok(Value::U32(as_timestamp(&args[0])?.hour()))
+            }
+            Builtin::Split => {
+                arity(2)?;
+
+                let (s, separator) = (as_str(&args[0])?, as_str(&args[1])?);
+
+                Ok(Value::Seq(
+                    s.split(separator)
+                        .map(|part| Value::String(part.to_string()))
+                        .collect(),
+                ))
+            }
+            Builtin::Concat => {
+                if args
+                    .iter()
+                    .all(|arg| matches!(unwrap_value(arg), Value::Seq(_)))
+                {
+                    let seq = args
+                        .iter()
+                        .flat_map(|arg| match unwrap_value(arg) {
+                            Value::Seq(seq) => seq.clone(),
+                            _ => Vec::new(),
+                        })
+                        .collect();
+
+                    return Ok(Value::Seq(seq));
+                }
+
+                args.iter()
+                    .map(as_str)
+                    .collect::<Result<String, _>>()
+                    .map(Value::String)
+            }
+        }
+    }
+}
+
+pub(crate) fn unwrap_optional(ty: &AttributeType) -> &AttributeType {
+    match ty {
+        AttributeType::Optional(inner) => unwrap_optional(inner),
+        ty => ty,
+    }
+}
+
+pub(crate) fn is_number(ty: &AttributeType) -> bool {
+    matches!(ty, AttributeType::Integer | AttributeType::Float)
+}
+
+/// Timestamps are also written as strings or unix seconds
+pub(crate) fn is_timestamp(ty: &AttributeType) -> bool {
+    matches!(
+        ty,
+        AttributeType::Timestamp
+            | AttributeType::Date
+            | AttributeType::String
+            | AttributeType::Integer
+    )
+}
+
+/// Check an argument type, unknown types are accepted and checked while evaluating
+fn expect(
+    ty: &AttributeType,
+    expected: &str,
+    accept: impl Fn(&AttributeType) -> bool,
+) -> Result<(), String> {
+    match unwrap_optional(ty) {
+        AttributeType::Any => Ok(()),
+        ty if accept(ty) => Ok(()),
+        ty => Err(format!("expects {expected}, got {ty:?}")),
+    }
+}
+
+pub(crate) fn unwrap_value(value: &Value) -> &Value {
+    match value {
+        Value::Option(Some(inner)) | Value::Newtype(inner) => unwrap_value(inner),
+        value => value,
+    }
+}
+
+fn as_str(value: &Value) -> Result<&str, String> {
+    match unwrap_value(value) {
+        Value::String(s) => Ok(s),
+        other => Err(format!("{other:?} isn't a string")),
+    }
+}
+
+/// Number as either an integer or a float, to compare values of different widths
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    pub(crate) fn from_value(value: &Value) -> Option<Self> {
+        let number = match unwrap_value(value) {
+            Value::U8(n) => Number::Int(*n as i128),
+            Value::U16(n) => Number::Int(*n as i128),
+            Value::U32(n) => Number::Int(*n as i128),
+            Value::U64(n) => Number::Int(*n as i128),
+            Value::I8(n) => Number::Int(*n as i128),
+            Value::I16(n) => Number::Int(*n as i128),
+            Value::I32(n) => Number::Int(*n as i128),
+            Value::I64(n) => Number::Int(*n as i128),
+            Value::F32(n) => Number::Float(*n as f64),
+            Value::F64(n) => Number::Float(*n),
+            _ => return None,
+        };
+
+        Some(number)
+    }
+
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => a.partial_cmp(b),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+}
+
+/// Order of two numbers by value, regardless of their width or sign, None for anything else
+pub(crate) fn numeric_cmp(a: &Value, b: &Value) -> Option<Ordering> {
+    compare_numbers(a, b).ok()
+}
+
+fn compare_numbers(a: &Value, b: &Value) -> Result<Ordering, String> {
+    let number = |value| Number::from_value(value).ok_or(format!("{value:?} isn't a number"));
+
+    number(a)?
+        .partial_cmp(&number(b)?)
+        .ok_or_else(|| "can't compare NaN".to_string())
+}
@@ -0,0 +1,133 @@
+use std::{any::type_name, fmt};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Error;
+
+/// Identifier of an entity as carried by a request, converted into the adapter's own `Id`
+///
+/// Serialized without a tag, eg. `"7d9f..."`, `42`, `"my-slug"` or `["acme", 42]`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EntityId {
+    Uuid(Uuid),
+    Int(i64),
+    String(String),
+    /// Key made of several columns, eg. tenant and number
+    Composite(Vec<EntityId>),
+}
+
+impl EntityId {
+    /// This id can't be converted into a `T`, eg. when implementing `TryFrom<EntityId>` for a composite key
+    pub fn invalid<T>(self) -> Error {
+        Error::InvalidEntityId {
+            id: self,
+            expected: type_name::<T>(),
+        }
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntityId::Uuid(id) => write!(f, "{id}"),
+            EntityId::Int(id) => write!(f, "{id}"),
+            EntityId::String(id) => f.write_str(id),
+            EntityId::Composite(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{part}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<Uuid> for EntityId {
+    fn from(id: Uuid) -> Self {
+        Self::Uuid(id)
+    }
+}
+
+impl From<i64> for EntityId {
+    fn from(id: i64) -> Self {
+        Self::Int(id)
+    }
+}
+
+impl From<i32> for EntityId {
+    fn from(id: i32) -> Self {
+        Self::Int(id.into())
+    }
+}
+
+impl From<String> for EntityId {
+    fn from(id: String) -> Self {
+        Self::String(id)
+    }
+}
+
+impl From<&str> for EntityId {
+    fn from(id: &str) -> Self {
+        Self::String(id.to_string())
+    }
+}
+
+impl From<Vec<EntityId>> for EntityId {
+    fn from(parts: Vec<EntityId>) -> Self {
+        Self::Composite(parts)
+    }
+}
+
+/// Also from its string form, eg. an id taken from the URL
+impl TryFrom<EntityId> for Uuid {
+    type Error = Error;
+
+    fn try_from(id: EntityId) -> Result<Self, Self::Error> {
+        match id {
+            EntityId::Uuid(id) => Ok(id),
+            EntityId::String(ref s) => s.parse().map_err(|_| id.invalid::<Self>()),
+            id => Err(id.invalid::<Self>()),
+        }
+    }
+}
+
+impl TryFrom<EntityId> for i64 {
+    type Error = Error;
+
+    fn try_from(id: EntityId) -> Result<Self, Self::Error> {
+        match id {
+            EntityId::Int(id) => Ok(id),
+            id => Err(id.invalid::<Self>()),
+        }
+    }
+}
+
+/// Slugs, a UUID is taken in its string form
+impl TryFrom<EntityId> for String {
+    type Error = Error;
+
+    fn try_from(id: EntityId) -> Result<Self, Self::Error> {
+        match id {
+            EntityId::String(id) => Ok(id),
+            EntityId::Uuid(id) => Ok(id.to_string()),
+            id => Err(id.invalid::<Self>()),
+        }
+    }
+}
+
+impl TryFrom<EntityId> for Vec<EntityId> {
+    type Error = Error;
+
+    fn try_from(id: EntityId) -> Result<Self, Self::Error> {
+        match id {
+            EntityId::Composite(parts) => Ok(parts),
+            id => Err(id.invalid::<Self>()),
+        }
+    }
+}
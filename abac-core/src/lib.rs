@@ -2,17 +2,49 @@
 mod tests;
 
 mod adapter;
+mod arithmetic;
+mod builder;
+mod definition;
 mod engine;
 mod entity;
 mod error;
 mod evaluator;
+mod function;
+mod id;
+mod network;
+mod path;
+mod policy;
+mod reference;
+mod relation;
+mod resolver;
+mod role;
 mod rules;
+mod schema;
+mod template;
+mod time;
+mod validator;
 
 pub use adapter::*;
+pub(crate) use arithmetic::*;
+pub use builder::*;
+pub use definition::*;
 pub use engine::*;
 pub use entity::*;
 pub use error::*;
 pub use evaluator::*;
+pub use function::*;
+pub use id::*;
 pub use macros::*;
+pub use network::*;
+pub use path::*;
+pub use policy::*;
+pub use reference::*;
+pub use relation::*;
+pub use resolver::*;
+pub use role::*;
 pub use rules::*;
+pub use schema::*;
 pub use serde_value;
+pub use template::*;
+pub use time::*;
+pub use uuid;
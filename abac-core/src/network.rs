@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_value::Value;
+
+use crate::{Attribute, AttributeType, Error, unwrap_value};
+
+/// Block of IP addresses, eg. `10.0.0.0/8` or `2001:db8::/32`, a bare address is a single host
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn new(network: IpAddr, prefix: u8) -> Result<Self, Error> {
+        let max = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+
+        if prefix > max {
+            return Err(Error::InvalidCidr(format!("{network}/{prefix}")));
+        }
+
+        Ok(Self { network, prefix })
+    }
+
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Is the address inside this block? IPv4-mapped IPv6 addresses match IPv4 blocks
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.network, canonical(*addr)) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        addr => addr,
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidCidr(s.to_string());
+
+        match s.split_once('/') {
+            Some((network, prefix)) => {
+                let network = network.parse::<IpAddr>().map_err(|_| invalid())?;
+                let prefix = prefix.parse::<u8>().map_err(|_| invalid())?;
+
+                Self::new(network, prefix).map_err(|_| invalid())
+            }
+            None => {
+                let network = s.parse::<IpAddr>().map_err(|_| invalid())?;
+                let prefix = if network.is_ipv4() { 32 } else { 128 };
+
+                Self::new(network, prefix)
+            }
+        }
+    }
+}
+
+impl Serialize for Cidr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cidr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Attribute for Cidr {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Cidr
+    }
+}
+
+impl Attribute for IpAddr {
+    fn attribute_type() -> AttributeType {
+        AttributeType::IpAddress
+    }
+}
+
+impl Attribute for Ipv4Addr {
+    fn attribute_type() -> AttributeType {
+        AttributeType::IpAddress
+    }
+}
+
+impl Attribute for Ipv6Addr {
+    fn attribute_type() -> AttributeType {
+        AttributeType::IpAddress
+    }
+}
+
+pub(crate) fn as_ip(value: &Value) -> Result<IpAddr, String> {
+    match unwrap_value(value) {
+        Value::String(s) => s.parse().map_err(|_| format!("{s:?} isn't an IP address")),
+        other => Err(format!("{other:?} isn't an IP address")),
+    }
+}
+
+/// Single block or a list of blocks, eg. an attribute holding the allowed ranges
+pub(crate) fn as_cidrs(value: &Value) -> Result<Vec<Cidr>, String> {
+    let cidr = |value: &Value| match unwrap_value(value) {
+        Value::String(s) => s.parse::<Cidr>().map_err(|err| err.to_string()),
+        other => Err(format!("{other:?} isn't a CIDR block")),
+    };
+
+    match unwrap_value(value) {
+        Value::Seq(blocks) => blocks.iter().map(cidr).collect(),
+        value => cidr(value).map(|block| vec![block]),
+    }
+}
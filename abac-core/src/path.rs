@@ -0,0 +1,219 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_value::Value;
+
+use crate::{Attr, Entity, Error};
+
+/// Single step of an attribute path
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    /// Struct field or map key, eg. `address` or `labels["env"]`
+    Key(String),
+    /// Sequence index, eg. `members[0]`
+    Index(usize),
+}
+
+/// Parsed attribute path like `address.country`, `labels["env"]` or `members[0].id`
+///
+/// Parsed once when the rule is built or deserialized, the raw form is kept for serialization
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttrPath {
+    raw: String,
+    segments: Vec<PathSegment>,
+}
+
+impl AttrPath {
+    pub fn parse(raw: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidPath(raw.to_string());
+
+        let mut segments = Vec::new();
+        let mut rest = raw;
+        let mut expect_key = true;
+
+        while !rest.is_empty() {
+            if let Some(inner) = rest.strip_prefix('[') {
+                // Brackets must follow a segment, `a.[0]` is invalid
+                if expect_key && !segments.is_empty() {
+                    return Err(invalid());
+                }
+
+                let end = inner.find(']').ok_or_else(invalid)?;
+                let index = inner[..end].trim();
+
+                let segment = match index.parse::<usize>() {
+                    Ok(index) => PathSegment::Index(index),
+                    Err(_) => PathSegment::Key(unquote(index).ok_or_else(invalid)?.to_string()),
+                };
+                segments.push(segment);
+
+                rest = &inner[end + 1..];
+                expect_key = false;
+            } else if let Some(inner) = rest.strip_prefix('.') {
+                if expect_key {
+                    return Err(invalid());
+                }
+
+                rest = inner;
+                expect_key = true;
+            } else {
+                if !expect_key {
+                    return Err(invalid());
+                }
+
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                segments.push(PathSegment::Key(rest[..end].to_string()));
+
+                rest = &rest[end..];
+                expect_key = false;
+            }
+        }
+
+        // Empty path or trailing dot
+        if expect_key {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            raw: raw.to_string(),
+            segments,
+        })
+    }
+
+    /// Path of a single struct field, eg. the name of a field generated by `#[derive(Entity)]`
+    pub(crate) fn key(name: &str) -> Self {
+        Self {
+            raw: name.to_string(),
+            segments: vec![PathSegment::Key(name.to_string())],
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The top level attribute of the entity
+    pub fn root(&self) -> Option<&str> {
+        match self.segments.first() {
+            Some(PathSegment::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Resolve the path against an entity, only the root attribute is asked to the entity
+    ///
+    /// None when the attribute doesn't exist, fails when it can't be serialized
+    pub fn resolve<'a>(&self, entity: &'a dyn Entity) -> Result<Option<Cow<'a, Value>>, Error> {
+        let Some(root) = self.root() else {
+            return Ok(None);
+        };
+
+        Ok(entity
+            .get_attribute(root)?
+            .and_then(|root| resolve_value(root, &self.segments[1..])))
+    }
+}
+
+/// Walk down a value through the remaining segments
+pub(crate) fn resolve_value<'a>(
+    value: Cow<'a, Value>,
+    segments: &[PathSegment],
+) -> Option<Cow<'a, Value>> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match value {
+            Cow::Borrowed(value) => descend_ref(value, segment).map(Cow::Borrowed),
+            Cow::Owned(value) => descend_owned(value, segment).map(Cow::Owned),
+        })
+}
+
+fn descend_ref<'a>(value: &'a Value, segment: &PathSegment) -> Option<&'a Value> {
+    match (value, segment) {
+        (Value::Option(Some(inner)) | Value::Newtype(inner), _) => descend_ref(inner, segment),
+        (Value::Map(map), PathSegment::Key(key)) => map.get(&Value::String(key.clone())),
+        (Value::Seq(seq), PathSegment::Index(index)) => seq.get(*index),
+        _ => None,
+    }
+}
+
+fn descend_owned(value: Value, segment: &PathSegment) -> Option<Value> {
+    match (value, segment) {
+        (Value::Option(Some(inner)) | Value::Newtype(inner), _) => descend_owned(*inner, segment),
+        (Value::Map(mut map), PathSegment::Key(key)) => map.remove(&Value::String(key.clone())),
+        (Value::Seq(mut seq), PathSegment::Index(index)) if *index < seq.len() => {
+            Some(seq.swap_remove(*index))
+        }
+        _ => None,
+    }
+}
+
+/// Key inside brackets, quoted with the same quote on both ends or bare
+fn unquote(key: &str) -> Option<&str> {
+    match key.chars().next()? {
+        quote @ ('"' | '\'') => key[1..].strip_suffix(quote),
+        // A bare key ending with a quote is missing its opening one
+        _ if key.ends_with(['"', '\'']) => None,
+        _ => Some(key),
+    }
+}
+
+impl FromStr for AttrPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for AttrPath {
+    type Error = Error;
+
+    fn try_from(raw: &str) -> Result<Self, Self::Error> {
+        Self::parse(raw)
+    }
+}
+
+impl TryFrom<String> for AttrPath {
+    type Error = Error;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        Self::parse(&raw)
+    }
+}
+
+/// Typed field of an entity, eg. `has_role(User::ROLES, "admin")`
+impl<E, T> From<Attr<E, T>> for AttrPath {
+    fn from(attr: Attr<E, T>) -> Self {
+        Self::key(attr.name())
+    }
+}
+
+impl PartialEq<str> for AttrPath {
+    fn eq(&self, other: &str) -> bool {
+        self.raw == other
+    }
+}
+
+impl fmt::Display for AttrPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for AttrPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for AttrPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+
+        Self::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Rules;
+
+/// Stored policies referenced from any `Rules` with `{ "Ref": "task.view" }`
+///
+/// Deserializes from a map of id to rules, eg. `{ "task.view": [[...]], "task.edit": [[...]] }`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Policies(HashMap<String, Rules>);
+
+/// Referenced policy replaced by its rules, the id is kept for explanation
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Policy {
+    pub(crate) id: String,
+    pub(crate) rules: Rules,
+}
+
+impl Policies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the policy as `id`, it may reference other policies
+    #[inline]
+    pub fn insert(mut self, id: impl Into<String>, rules: impl Into<Rules>) -> Self {
+        self.0.insert(id.into(), rules.into());
+
+        self
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Rules> {
+        self.0.get(id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Add every policy of `other`, replacing the ones with the same id
+    pub fn extend(&mut self, other: Policies) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn get_key_value(&self, id: &str) -> Option<(&String, &Rules)> {
+        self.0.get_key_value(id)
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use serde_value::Value;
+
+use crate::{
+    AttributeSchema, AttributeType, Engine, Entity, EntityId, EntityValue, Error, EvaluateEntity,
+    LoadResult, PathSegment,
+};
+
+/// Attribute holding the id of another registered entity, eg. `#[abac(ref = "project")] project_id: Uuid`
+///
+/// The id may be of any form the referenced adapter accepts, see `EntityId`
+///
+/// Rules traverse it through the alias, eg. `project.owner`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reference {
+    pub field: &'static str,
+    pub entity: &'static str,
+    pub alias: &'static str,
+}
+
+impl Reference {
+    pub const fn new(field: &'static str, entity: &'static str, alias: &'static str) -> Self {
+        Self {
+            field,
+            entity,
+            alias,
+        }
+    }
+}
+
+/// Loaded entities within a single request along with their loaded fields,
+/// keyed by entity name and id, the fields are None when loaded whole
+pub(crate) type LoadedEntities =
+    HashMap<(&'static str, EntityId), (Option<Vec<String>>, Arc<dyn Entity>)>;
+
+/// Entity along with the attributes that don't come from the entity itself,
+/// the traversed references and the resolved attributes
+pub(crate) struct LinkedEntity<'a> {
+    entity: Arc<dyn Entity + 'a>,
+    links: HashMap<&'static str, Value>,
+    /// Types of the links, so temporal attributes of a reference are still known as such
+    link_types: HashMap<&'static str, AttributeType>,
+}
+
+impl Entity for LinkedEntity<'_> {
+    fn to_value(&self) -> Result<EntityValue, Error> {
+        let mut value = self.entity.to_value()?;
+        value.extend(
+            self.links
+                .iter()
+                .map(|(alias, link)| (alias.to_string(), link.clone())),
+        );
+
+        Ok(value)
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        self.entity.field_names()
+    }
+
+    fn get_attribute(&self, name: &str) -> Result<Option<Cow<'_, Value>>, Error> {
+        match self.links.get(name) {
+            Some(link) => Ok(Some(Cow::Borrowed(link))),
+            None => self.entity.get_attribute(name),
+        }
+    }
+
+    fn attributes(&self) -> Vec<AttributeSchema> {
+        let mut attributes = self.entity.attributes();
+        for (name, ty) in &self.link_types {
+            attributes.retain(|attr| attr.name != *name);
+            attributes.push(AttributeSchema::new(*name, ty.clone()));
+        }
+
+        attributes
+    }
+
+    fn references(&self) -> &'static [Reference] {
+        self.entity.references()
+    }
+}
+
+impl Engine {
+    /// Load the referenced entities used by the paths, recursively up to `max_depth`,
+    /// and resolve the used attributes that have a resolver
+    pub(crate) fn link<'a, 'e: 'a>(
+        &'a self,
+        name: &'a str,
+        id: Option<EntityId>,
+        entity: Arc<dyn Entity + 'e>,
+        paths: Vec<&'a [PathSegment]>,
+        depth: usize,
+        loaded: &'a mut LoadedEntities,
+    ) -> LoadResult<'a, LinkedEntity<'e>> {
+        Box::pin(async move {
+            let mut links = HashMap::new();
+            let mut link_types = HashMap::new();
+
+            for reference in entity.references() {
+                let rest = paths
+                    .iter()
+                    .filter_map(|path| match path.split_first() {
+                        Some((PathSegment::Key(key), rest)) if key == reference.alias => Some(rest),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                if rest.is_empty() {
+                    continue;
+                }
+
+                if depth >= self.max_depth {
+                    return Err(Error::MaxDepthExceeded(self.max_depth));
+                }
+
+                // Null reference, nothing to traverse
+                let id = match entity.get_attribute(reference.field)?.map(Cow::into_owned) {
+                    None | Some(Value::Unit | Value::Option(None)) => continue,
+                    Some(value) => match Option::<EntityId>::deserialize(value.clone()) {
+                        Ok(Some(id)) => id,
+                        _ => {
+                            return Err(Error::InvalidEntityId {
+                                id: EntityId::String(format!("{value:?}")),
+                                expected: "an entity id",
+                            });
+                        }
+                    },
+                };
+
+                // Reuse the loaded entity, unless it was loaded without some needed fields
+                let fields = self.projected_fields(reference.entity, &rest);
+                let target = match loaded.get(&(reference.entity, id.clone())) {
+                    Some((loaded_fields, target)) if covers(loaded_fields, &fields) => {
+                        target.clone()
+                    }
+                    _ => {
+                        let target: Arc<dyn Entity> = self
+                            .load_projected(
+                                EvaluateEntity::by_id(reference.entity, id.clone()),
+                                fields.as_deref(),
+                            )
+                            .await?
+                            .into();
+                        let fields =
+                            fields.map(|fields| fields.iter().map(ToString::to_string).collect());
+                        loaded.insert((reference.entity, id.clone()), (fields, target.clone()));
+
+                        target
+                    }
+                };
+
+                let linked = self
+                    .link(
+                        reference.entity,
+                        Some(id),
+                        target,
+                        rest.clone(),
+                        depth + 1,
+                        loaded,
+                    )
+                    .await?;
+
+                // Only the attributes used by the rules
+                let mut value = BTreeMap::new();
+                for path in &rest {
+                    let Some(PathSegment::Key(key)) = path.first() else {
+                        continue;
+                    };
+
+                    if let Some(attribute) = linked.get_attribute(key)? {
+                        value.insert(Value::String(key.clone()), attribute.into_owned());
+                    }
+                }
+
+                links.insert(reference.alias, Value::Map(value));
+                link_types.insert(reference.alias, AttributeType::Struct(linked.attributes()));
+            }
+
+            // Resolve the used attributes, each one only once
+            if let Some(resolvers) = self.resolvers.get(name) {
+                for path in &paths {
+                    let Some(PathSegment::Key(key)) = path.first() else {
+                        continue;
+                    };
+                    let Some((attribute, resolver)) = resolvers.get_key_value(key.as_str()) else {
+                        continue;
+                    };
+
+                    if links.contains_key(attribute) {
+                        continue;
+                    }
+
+                    let provider =
+                        self.provider(resolver.provider_type(), resolver.provider_name())?;
+                    let value = resolver
+                        .resolve(id.as_ref(), entity.as_ref(), provider)
+                        .await?;
+
+                    links.insert(*attribute, value);
+                    link_types.insert(*attribute, resolver.attribute_type());
+                }
+            }
+
+            Ok(LinkedEntity {
+                entity,
+                links,
+                link_types,
+            })
+        })
+    }
+}
+
+/// Were the needed fields loaded already? A whole entity has every field
+fn covers(loaded: &Option<Vec<String>>, needed: &Option<Vec<&str>>) -> bool {
+    match (loaded, needed) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(loaded), Some(needed)) => {
+            needed.iter().all(|field| loaded.iter().any(|f| f == field))
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, LoadResult};
+
+/// How deep a relation check may recurse through usersets and rewrites
+pub const MAX_RELATION_DEPTH: usize = 16;
+
+/// Object in the relation graph, written as `namespace:id` (eg. `doc:readme`)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectRef {
+    pub namespace: String,
+    pub id: String,
+}
+
+impl ObjectRef {
+    pub fn new(namespace: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            id: id.into(),
+        }
+    }
+}
+
+impl fmt::Display for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.id)
+    }
+}
+
+impl FromStr for ObjectRef {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((namespace, id)) if !namespace.is_empty() && !id.is_empty() => {
+                Ok(Self::new(namespace, id))
+            }
+            _ => Err(Error::InvalidTuple(s.to_string())),
+        }
+    }
+}
+
+/// Who holds the relation, either an object directly (`user:alice`)
+/// or everyone having a relation on another object (`group:eng#member`)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TupleSubject {
+    Direct(ObjectRef),
+    Userset(ObjectRef, String),
+}
+
+impl TupleSubject {
+    pub fn object(&self) -> &ObjectRef {
+        match self {
+            TupleSubject::Direct(object) | TupleSubject::Userset(object, _) => object,
+        }
+    }
+}
+
+impl fmt::Display for TupleSubject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TupleSubject::Direct(object) => write!(f, "{object}"),
+            TupleSubject::Userset(object, relation) => write!(f, "{object}#{relation}"),
+        }
+    }
+}
+
+impl FromStr for TupleSubject {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('#') {
+            Some((object, relation)) if !relation.is_empty() => {
+                Ok(Self::Userset(object.parse()?, relation.to_string()))
+            }
+            Some(_) => Err(Error::InvalidTuple(s.to_string())),
+            None => Ok(Self::Direct(s.parse()?)),
+        }
+    }
+}
+
+/// Relationship tuple, written as `object#relation@subject` (eg. `doc:readme#viewer@user:alice`)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelationTuple {
+    pub object: ObjectRef,
+    pub relation: String,
+    pub subject: TupleSubject,
+}
+
+impl fmt::Display for RelationTuple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}@{}", self.object, self.relation, self.subject)
+    }
+}
+
+impl FromStr for RelationTuple {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidTuple(s.to_string());
+
+        let (object_relation, subject) = s.split_once('@').ok_or_else(invalid)?;
+        let (object, relation) = object_relation.split_once('#').ok_or_else(invalid)?;
+
+        if relation.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            object: object.parse().map_err(|_| invalid())?,
+            relation: relation.to_string(),
+            subject: subject.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Storage of relationship tuples, eg. a database table
+///
+/// The engine reads the tuples of every relation the rules use before evaluating them
+pub trait TupleStore: Send + Sync {
+    /// Every subject holding the relation on the object
+    fn read<'a>(
+        &'a self,
+        object: &'a ObjectRef,
+        relation: &'a str,
+    ) -> LoadResult<'a, Vec<TupleSubject>>;
+}
+
+impl<T: TupleStore + ?Sized> TupleStore for Arc<T> {
+    fn read<'a>(
+        &'a self,
+        object: &'a ObjectRef,
+        relation: &'a str,
+    ) -> LoadResult<'a, Vec<TupleSubject>> {
+        (**self).read(object, relation)
+    }
+}
+
+/// Tuple store living in memory, handy for tests and small deployments
+#[derive(Default)]
+pub struct InMemoryTupleStore {
+    tuples: RwLock<HashMap<(ObjectRef, String), Vec<TupleSubject>>>,
+}
+
+impl InMemoryTupleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&self, tuple: RelationTuple) {
+        let mut tuples = self.tuples.write().unwrap_or_else(|err| err.into_inner());
+        let subjects = tuples.entry((tuple.object, tuple.relation)).or_default();
+
+        if !subjects.contains(&tuple.subject) {
+            subjects.push(tuple.subject);
+        }
+    }
+
+    pub fn delete(&self, tuple: &RelationTuple) {
+        let mut tuples = self.tuples.write().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(subjects) = tuples.get_mut(&(tuple.object.clone(), tuple.relation.clone())) {
+            subjects.retain(|subject| *subject != tuple.subject);
+        }
+    }
+}
+
+impl TupleStore for InMemoryTupleStore {
+    fn read<'a>(
+        &'a self,
+        object: &'a ObjectRef,
+        relation: &'a str,
+    ) -> LoadResult<'a, Vec<TupleSubject>> {
+        let tuples = self.tuples.read().unwrap_or_else(|err| err.into_inner());
+        let subjects = tuples
+            .get(&(object.clone(), relation.to_string()))
+            .cloned()
+            .unwrap_or_default();
+
+        Box::pin(async move { Ok(subjects) })
+    }
+}
+
+/// Outcome of a relation the rules check, read before evaluating them
+#[derive(Clone, Debug)]
+pub(crate) struct RelationCheck {
+    pub(crate) object: ObjectRef,
+    pub(crate) relation: String,
+    pub(crate) subject: ObjectRef,
+    pub(crate) granted: bool,
+}
+
+impl RelationCheck {
+    pub(crate) fn is(&self, object: &ObjectRef, relation: &str, subject: &ObjectRef) -> bool {
+        self.object == *object && self.relation == relation && self.subject == *subject
+    }
+}
+
+/// How a relation is computed, relations without a rewrite only use their own tuples
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rewrite {
+    /// Tuples stored directly under this relation
+    This,
+    /// Everyone having another relation on the same object, eg. editors are viewers
+    Computed(String),
+    /// Follow `tupleset` to other objects and use their `computed` relation,
+    /// eg. viewers of the parent folder are viewers of the doc
+    TupleToUserset {
+        tupleset: String,
+        computed: String,
+    },
+    Union(Vec<Rewrite>),
+}
+
+/// Relationship subsystem, tuples along with the rewrite rules of each relation
+pub struct Relations {
+    store: Box<dyn TupleStore>,
+    rewrites: HashMap<(String, String), Rewrite>,
+}
+
+impl Relations {
+    pub fn new(store: impl TupleStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+            rewrites: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_rewrite(
+        mut self,
+        namespace: impl Into<String>,
+        relation: impl Into<String>,
+        rewrite: Rewrite,
+    ) -> Self {
+        self.rewrites
+            .insert((namespace.into(), relation.into()), rewrite);
+
+        self
+    }
+
+    /// Does the subject have the relation on the object?
+    ///
+    /// A relation reached again while checking, eg. a group containing itself, grants nothing
+    pub fn check<'a>(
+        &'a self,
+        object: &'a ObjectRef,
+        relation: &'a str,
+        subject: &'a ObjectRef,
+    ) -> LoadResult<'a, bool> {
+        Box::pin(async move {
+            let mut visited = HashSet::new();
+
+            self.check_depth(object, relation, subject, 0, &mut visited)
+                .await
+        })
+    }
+
+    fn check_depth<'a>(
+        &'a self,
+        object: &'a ObjectRef,
+        relation: &'a str,
+        subject: &'a ObjectRef,
+        depth: usize,
+        visited: &'a mut HashSet<(ObjectRef, String)>,
+    ) -> LoadResult<'a, bool> {
+        Box::pin(async move {
+            if depth > MAX_RELATION_DEPTH {
+                return Err(Error::RelationDepthExceeded(MAX_RELATION_DEPTH));
+            }
+            if !visited.insert((object.clone(), relation.to_string())) {
+                return Ok(false);
+            }
+
+            let rewrite = self
+                .rewrites
+                .get(&(object.namespace.clone(), relation.to_string()))
+                .unwrap_or(&Rewrite::This);
+
+            self.check_rewrite(rewrite, object, relation, subject, depth, visited)
+                .await
+        })
+    }
+
+    fn check_rewrite<'a>(
+        &'a self,
+        rewrite: &'a Rewrite,
+        object: &'a ObjectRef,
+        relation: &'a str,
+        subject: &'a ObjectRef,
+        depth: usize,
+        visited: &'a mut HashSet<(ObjectRef, String)>,
+    ) -> LoadResult<'a, bool> {
+        Box::pin(async move {
+            match rewrite {
+                Rewrite::This => {
+                    for holder in self.store.read(object, relation).await? {
+                        let pass = match &holder {
+                            TupleSubject::Direct(holder) => holder == subject,
+                            TupleSubject::Userset(holder, holder_relation) => {
+                                self.check_depth(
+                                    holder,
+                                    holder_relation,
+                                    subject,
+                                    depth + 1,
+                                    visited,
+                                )
+                                .await?
+                            }
+                        };
+
+                        if pass {
+                            return Ok(true);
+                        }
+                    }
+
+                    Ok(false)
+                }
+                Rewrite::Computed(computed) => {
+                    self.check_depth(object, computed, subject, depth + 1, visited)
+                        .await
+                }
+                Rewrite::TupleToUserset { tupleset, computed } => {
+                    for holder in self.store.read(object, tupleset).await? {
+                        if self
+                            .check_depth(holder.object(), computed, subject, depth + 1, visited)
+                            .await?
+                        {
+                            return Ok(true);
+                        }
+                    }
+
+                    Ok(false)
+                }
+                Rewrite::Union(rewrites) => {
+                    for rewrite in rewrites {
+                        if self
+                            .check_rewrite(rewrite, object, relation, subject, depth, visited)
+                            .await?
+                        {
+                            return Ok(true);
+                        }
+                    }
+
+                    Ok(false)
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use std::any::{Any, TypeId};
+
+use crate::{AttributeType, Entity, EntityId, Error, LoadResult};
+use serde_value::Value;
+
+/// Resolve a single attribute that doesn't live on the loaded entity (eg. risk score, HR department)
+///
+/// Only invoked when a rule references the attribute
+pub trait AttributeResolver: Send + Sync {
+    type Provider: Any + Send + Sync;
+
+    /// Name of the provider registered with `Engine::with_named_provider`, if any
+    const PROVIDER_NAME: Option<&'static str> = None;
+
+    fn resolve<'a>(
+        &'a self,
+        id: Option<&'a EntityId>,
+        entity: &'a dyn Entity,
+        provider: &'a Self::Provider,
+    ) -> LoadResult<'a, Value>;
+
+    /// Type of the resolved attribute, shown in the schema
+    fn attribute_type(&self) -> AttributeType {
+        AttributeType::Any
+    }
+
+    /// Fields of the entity the resolver reads, eg. `&["department"]`
+    ///
+    /// The entity is loaded whole for the resolver unless it declares them
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        None
+    }
+}
+
+pub(crate) trait DynResolver: Send + Sync {
+    /// Which provider does resolver need?
+    fn provider_type(&self) -> TypeId;
+
+    /// Which named provider does resolver need, if any?
+    fn provider_name(&self) -> Option<&'static str>;
+
+    fn attribute_type(&self) -> AttributeType;
+
+    fn fields(&self) -> Option<&'static [&'static str]>;
+
+    /// Resolve the attribute
+    fn resolve<'a>(
+        &'a self,
+        id: Option<&'a EntityId>,
+        entity: &'a dyn Entity,
+        provider: &'a (dyn Any + Send + Sync),
+    ) -> LoadResult<'a, Value>;
+}
+
+impl<T> DynResolver for T
+where
+    T: AttributeResolver + 'static,
+{
+    fn provider_type(&self) -> TypeId {
+        TypeId::of::<T::Provider>()
+    }
+
+    fn provider_name(&self) -> Option<&'static str> {
+        T::PROVIDER_NAME
+    }
+
+    fn attribute_type(&self) -> AttributeType {
+        AttributeResolver::attribute_type(self)
+    }
+
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        AttributeResolver::fields(self)
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        id: Option<&'a EntityId>,
+        entity: &'a dyn Entity,
+        provider: &'a (dyn Any + Send + Sync),
+    ) -> LoadResult<'a, Value> {
+        Box::pin(async move {
+            // Get the right provider
+            let provider = provider
+                .downcast_ref::<T::Provider>()
+                .ok_or(Error::ProviderNotFound)?;
+
+            AttributeResolver::resolve(self, id, entity, provider).await
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_value::Value;
+
+/// Role inheritance along with the permissions granted by each role
+///
+/// eg. admin inherits editor, editor inherits viewer, so an admin is also a viewer
+#[derive(Clone, Debug, Default)]
+pub struct RoleGraph {
+    inherits: HashMap<String, Vec<String>>,
+    permissions: HashMap<String, Vec<String>>,
+}
+
+impl RoleGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `role` gets everything the `inherited` roles have
+    #[inline]
+    pub fn inherit(
+        mut self,
+        role: impl Into<String>,
+        inherited: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.inherits
+            .entry(role.into())
+            .or_default()
+            .extend(inherited.into_iter().map(Into::into));
+
+        self
+    }
+
+    /// `role` is granted the `permissions`
+    #[inline]
+    pub fn permit(
+        mut self,
+        role: impl Into<String>,
+        permissions: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.permissions
+            .entry(role.into())
+            .or_default()
+            .extend(permissions.into_iter().map(Into::into));
+
+        self
+    }
+
+    /// Every role implied by the held roles, including themselves
+    pub fn implied_roles<'a>(
+        &'a self,
+        held: impl IntoIterator<Item = &'a str>,
+    ) -> HashSet<&'a str> {
+        let mut implied = HashSet::new();
+        let mut pending = held.into_iter().collect::<Vec<_>>();
+
+        // Visited roles are skipped, so cycles can't loop forever
+        while let Some(role) = pending.pop() {
+            if implied.insert(role) {
+                pending.extend(
+                    self.inherits
+                        .get(role)
+                        .into_iter()
+                        .flatten()
+                        .map(String::as_str),
+                );
+            }
+        }
+
+        implied
+    }
+
+    pub fn has_role<'a>(&'a self, held: impl IntoIterator<Item = &'a str>, role: &str) -> bool {
+        self.implied_roles(held).contains(role)
+    }
+
+    pub fn has_permission<'a>(
+        &'a self,
+        held: impl IntoIterator<Item = &'a str>,
+        permission: &str,
+    ) -> bool {
+        self.implied_roles(held).into_iter().any(|role| {
+            self.permissions
+                .get(role)
+                .is_some_and(|permissions| permissions.iter().any(|p| p == permission))
+        })
+    }
+}
+
+/// Roles held by an entity, the attribute can be a single role or a list of roles
+pub(crate) fn held_roles(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(role) => vec![role.as_str()],
+        Value::Seq(roles) => roles.iter().flat_map(held_roles).collect(),
+        Value::Option(Some(inner)) | Value::Newtype(inner) => held_roles(inner),
+        _ => Vec::new(),
+    }
+}
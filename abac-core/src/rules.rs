@@ -1,11 +1,45 @@
-use serde::{Deserialize, Serialize};
-use serde_value::Value;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_value::{Value, ValueDeserializer};
+
+use crate::{
+    AttrPath, Cidr, Definition, Duration, Error, Expression, FunctionCall, Inlining, Policy,
+    Schedule, unwrap_value,
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum SideRule {
-    Subject(/* Field Name */ String),
-    Object(/* Field Name */ String),
+    Subject(/* Field Path */ AttrPath),
+    Object(/* Field Path */ AttrPath),
+    /// Other entity of the request, eg. `{ "Entity": ["tenant", "plan"] }`
+    Entity(/* Alias */ String, /* Field Path */ AttrPath),
     Literal(/* Literal Value */ Value),
+    Function(/* Name & Arguments */ FunctionCall),
+    /// Element bound by the enclosing `Any`/`All`, the element itself without a path
+    Element(/* Field Path */ Option<AttrPath>),
+    Arithmetic(/* Operands & Operator */ Box<Expression>),
+    Cidr(/* Parsed Blocks */ Vec<Cidr>),
+    /// Placeholder of a `Template`, replaced by a literal when instantiated
+    Param(/* Parameter Name */ String),
+}
+
+impl SideRule {
+    /// Attribute paths along with the entity they're read from, including nested operands
+    pub(crate) fn paths(&self) -> Vec<(Target, &AttrPath)> {
+        match self {
+            SideRule::Subject(path) => vec![(Target::Subject, path)],
+            SideRule::Object(path) => vec![(Target::Object, path)],
+            SideRule::Entity(alias, path) => vec![(Target::Entity(alias.clone()), path)],
+            // Element paths are read from the collection, not from the entity
+            SideRule::Literal(_)
+            | SideRule::Cidr(_)
+            | SideRule::Element(_)
+            | SideRule::Param(_) => Vec::new(),
+            SideRule::Function(call) => call.args.iter().flat_map(SideRule::paths).collect(),
+            SideRule::Arithmetic(expression) => {
+                [expression.left.paths(), expression.right.paths()].concat()
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -15,14 +49,291 @@ pub(crate) enum Operator {
     Less,
     GreaterEqual,
     LessEqual,
+    /// Left timestamp is earlier than the right one
+    Before,
+    /// Left timestamp is later than the right one
+    After,
+    /// Both timestamps are at most the duration apart
+    Within(Duration),
+    /// Left IP address is inside one of the right CIDR blocks
+    InCidr,
+    /// Left IP address is outside every right CIDR block
+    NotInCidr,
+    /// Custom function registered on the `Engine`, called with the left and right sides
+    Custom(/* Function Name */ String),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub(crate) struct Rule {
+#[serde(try_from = "RawRule")]
+pub struct Rule {
     pub(crate) left: SideRule,
     pub(crate) operator: Operator,
     pub(crate) right: SideRule,
 }
 
+/// Rule as written, before its literals are parsed
+#[derive(Deserialize)]
+struct RawRule {
+    left: SideRule,
+    operator: Operator,
+    right: SideRule,
+}
+
+impl TryFrom<RawRule> for Rule {
+    type Error = Error;
+
+    fn try_from(raw: RawRule) -> Result<Self, Self::Error> {
+        Self::new(raw.left, raw.operator, raw.right)
+    }
+}
+
+impl Rule {
+    /// CIDR literals are parsed once here instead of on every evaluation
+    pub(crate) fn new(left: SideRule, operator: Operator, right: SideRule) -> Result<Self, Error> {
+        let right = match (&operator, right) {
+            (Operator::InCidr | Operator::NotInCidr, SideRule::Literal(value)) => {
+                let cidr = |value: &Value| match unwrap_value(value) {
+                    Value::String(s) => s.parse::<Cidr>(),
+                    other => Err(Error::InvalidCidr(format!("{other:?}"))),
+                };
+                let blocks = match unwrap_value(&value) {
+                    Value::Seq(blocks) => blocks.iter().map(cidr).collect::<Result<_, _>>()?,
+                    value => vec![cidr(value)?],
+                };
+
+                SideRule::Cidr(blocks)
+            }
+            (_, right) => right,
+        };
+
+        Ok(Self {
+            left,
+            operator,
+            right,
+        })
+    }
+}
+
+/// Which of the evaluated entities
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Target {
+    Subject,
+    Object,
+    /// Other entity of the request, referenced by its alias
+    Entity(/* Alias */ String),
+}
+
+/// `subject` has `relation` on `object` according to the relationship tuples
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Rules(pub(crate) Vec<Vec<Rule>>);
+pub struct RelationRule {
+    pub(crate) subject: Target,
+    pub(crate) relation: String,
+    pub(crate) object: Target,
+}
+
+/// Subject's role `attribute` holds `role`, directly or through inheritance
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoleRule {
+    pub(crate) attribute: AttrPath,
+    pub(crate) role: String,
+}
+
+/// One of the subject's roles in `attribute` is granted `permission`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PermissionRule {
+    pub(crate) attribute: AttrPath,
+    pub(crate) permission: String,
+}
+
+/// Nested rules checked against each element of a collection, eg. `Any` member that is a maintainer
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Quantifier {
+    pub(crate) collection: SideRule,
+    pub(crate) rules: Rules,
+}
+
+/// Single condition inside `Rules`
+///
+/// Plain comparison keeps its untagged form, eg. `{ "left": ..., "operator": ..., "right": ... }`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub enum Condition {
+    HasRelation(RelationRule),
+    HasRole(RoleRule),
+    HasPermission(PermissionRule),
+    /// Current time is inside the schedule
+    During(Schedule),
+    /// At least one element passes the nested rules
+    Any(Quantifier),
+    /// Every element passes the nested rules, an empty collection passes
+    All(Quantifier),
+    /// Condition declared once in the `Definitions`
+    Named(/* Definition Name */ String),
+    /// Named condition replaced by its rules, see `Definitions::inline`
+    Inlined(Definition),
+    /// Whole policy stored on the `Engine`, passes when its rules pass
+    Ref(/* Policy Id */ String),
+    /// Referenced policy replaced by its rules, see `Engine::inline`
+    Included(Policy),
+    #[serde(untagged)]
+    Compare(Rule),
+}
+
+/// Tags of the conditions, anything else is a plain comparison
+const TAGGED: &[&str] = &[
+    "HasRelation",
+    "HasRole",
+    "HasPermission",
+    "During",
+    "Any",
+    "All",
+    "Named",
+    "Inlined",
+    "Ref",
+    "Included",
+];
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Condition::serialize(self, serializer)
+    }
+}
+
+/// Comparisons are tried last and keep their own error, eg. an invalid path or CIDR block
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let tagged = match &value {
+            Value::Map(map) if map.len() == 1 => map
+                .keys()
+                .all(|key| matches!(key, Value::String(tag) if TAGGED.contains(&tag.as_str()))),
+            _ => false,
+        };
+
+        match tagged {
+            true => Condition::deserialize(ValueDeserializer::<D::Error>::new(value)),
+            false => {
+                Rule::deserialize(ValueDeserializer::<D::Error>::new(value)).map(Self::Compare)
+            }
+        }
+    }
+}
+
+impl From<Rule> for Condition {
+    fn from(rule: Rule) -> Self {
+        Self::Compare(rule)
+    }
+}
+
+impl From<RelationRule> for Condition {
+    fn from(rule: RelationRule) -> Self {
+        Self::HasRelation(rule)
+    }
+}
+
+impl From<RoleRule> for Condition {
+    fn from(rule: RoleRule) -> Self {
+        Self::HasRole(rule)
+    }
+}
+
+impl From<PermissionRule> for Condition {
+    fn from(rule: PermissionRule) -> Self {
+        Self::HasPermission(rule)
+    }
+}
+
+impl From<Schedule> for Condition {
+    fn from(schedule: Schedule) -> Self {
+        Self::During(schedule)
+    }
+}
+
+/// Outer list is AND-ed, each inner list is OR-ed
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rules(pub(crate) Vec<Vec<Condition>>);
+
+impl Rules {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Condition that must pass on its own
+    pub fn and(mut self, condition: impl Into<Condition>) -> Self {
+        self.0.push(vec![condition.into()]);
+
+        self
+    }
+
+    /// Group of conditions where at least one must pass
+    pub fn and_any(mut self, conditions: impl IntoIterator<Item = impl Into<Condition>>) -> Self {
+        self.0
+            .push(conditions.into_iter().map(Into::into).collect());
+
+        self
+    }
+}
+
+impl Rules {
+    /// Every attribute path used by the conditions, along with the entity it's read from
+    pub(crate) fn paths(&self) -> Vec<(Target, &AttrPath)> {
+        self.0
+            .iter()
+            .flatten()
+            .flat_map(|condition| match condition {
+                Condition::Compare(rule) => [rule.left.paths(), rule.right.paths()].concat(),
+                Condition::HasRelation(_) | Condition::During(_) => Vec::new(),
+                Condition::HasRole(rule) => vec![(Target::Subject, &rule.attribute)],
+                Condition::HasPermission(rule) => vec![(Target::Subject, &rule.attribute)],
+                Condition::Any(quantifier) | Condition::All(quantifier) => {
+                    [quantifier.collection.paths(), quantifier.rules.paths()].concat()
+                }
+                // Paths of named conditions and policies are compiled by the engine
+                Condition::Named(_) | Condition::Ref(_) => Vec::new(),
+                Condition::Inlined(definition) => definition.rules.paths(),
+                Condition::Included(policy) => policy.rules.paths(),
+            })
+            .collect()
+    }
+
+    /// Relations checked by the conditions, nested ones included
+    pub(crate) fn relations(&self) -> Vec<&RelationRule> {
+        self.0
+            .iter()
+            .flatten()
+            .flat_map(|condition| match condition {
+                Condition::HasRelation(rule) => vec![rule],
+                Condition::Any(quantifier) | Condition::All(quantifier) => {
+                    quantifier.rules.relations()
+                }
+                Condition::Inlined(definition) => definition.rules.relations(),
+                Condition::Included(policy) => policy.rules.relations(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Named conditions and policies referenced by the conditions, nested ones included
+    pub(crate) fn references(&self) -> Vec<Inlining<'_>> {
+        self.0
+            .iter()
+            .flatten()
+            .flat_map(|condition| match condition {
+                Condition::Named(name) => vec![Inlining::Named(name)],
+                Condition::Ref(id) => vec![Inlining::Policy(id)],
+                Condition::Any(quantifier) | Condition::All(quantifier) => {
+                    quantifier.rules.references()
+                }
+                Condition::Inlined(definition) => definition.rules.references(),
+                Condition::Included(policy) => policy.rules.references(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+impl<C: Into<Condition>> From<C> for Rules {
+    fn from(condition: C) -> Self {
+        Self(vec![vec![condition.into()]])
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    any::type_name,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "json-schema")]
+use serde_json::{Map, Value as JsonValue, json};
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{AttrPath, PathSegment};
+
+/// Type of an attribute, as seen by the rules after serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AttributeType {
+    Bool,
+    Integer,
+    Float,
+    String,
+    /// Date and time, serialized as an RFC 3339 string
+    Timestamp,
+    /// Calendar date, serialized as `YYYY-MM-DD`
+    Date,
+    /// Length of time, serialized as eg. `1h30m`
+    Duration,
+    /// IPv4 or IPv6 address
+    IpAddress,
+    /// Block of IP addresses, eg. `10.0.0.0/8`
+    Cidr,
+    Optional(Box<AttributeType>),
+    List(Box<AttributeType>),
+    Map(Box<AttributeType>),
+    Struct(Vec<AttributeSchema>),
+    /// Type is unknown, anything could be here
+    Any,
+}
+
+impl AttributeType {
+    #[cfg(feature = "json-schema")]
+    fn to_json_schema(&self) -> JsonValue {
+        match self {
+            AttributeType::Bool => json!({ "type": "boolean" }),
+            AttributeType::Integer => json!({ "type": "integer" }),
+            AttributeType::Float => json!({ "type": "number" }),
+            AttributeType::String => json!({ "type": "string" }),
+            AttributeType::Timestamp => json!({ "type": "string", "format": "date-time" }),
+            AttributeType::Date => json!({ "type": "string", "format": "date" }),
+            AttributeType::Duration => json!({ "type": "string" }),
+            AttributeType::IpAddress => {
+                json!({ "type": "string", "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }] })
+            }
+            AttributeType::Cidr => json!({ "type": "string" }),
+            AttributeType::Optional(inner) => {
+                json!({ "anyOf": [inner.to_json_schema(), { "type": "null" }] })
+            }
+            AttributeType::List(inner) => {
+                json!({ "type": "array", "items": inner.to_json_schema() })
+            }
+            AttributeType::Map(inner) => {
+                json!({ "type": "object", "additionalProperties": inner.to_json_schema() })
+            }
+            AttributeType::Struct(attributes) => object_json_schema(attributes),
+            AttributeType::Any => json!({}),
+        }
+    }
+
+    /// Type found after walking down the segments, None when the path doesn't exist
+    pub(crate) fn segments_type(&self, segments: &[PathSegment]) -> Option<AttributeType> {
+        segments
+            .iter()
+            .try_fold(self.clone(), |ty, segment| ty.descend(segment))
+    }
+
+    /// Type after walking down one segment, None when it can't go there
+    fn descend(&self, segment: &PathSegment) -> Option<AttributeType> {
+        match (self, segment) {
+            (AttributeType::Optional(inner), _) => inner.descend(segment),
+            (AttributeType::Struct(attributes), PathSegment::Key(key)) => attributes
+                .iter()
+                .find(|attr| attr.name == *key)
+                .map(|attr| attr.ty.clone()),
+            (AttributeType::Map(inner), PathSegment::Key(_)) => Some(*inner.clone()),
+            (AttributeType::List(inner), PathSegment::Index(_)) => Some(*inner.clone()),
+            (AttributeType::Any, _) => Some(AttributeType::Any),
+            _ => None,
+        }
+    }
+}
+
+/// Name and type of a single attribute
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttributeSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: AttributeType,
+}
+
+impl AttributeSchema {
+    pub fn new(name: impl Into<String>, ty: AttributeType) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+        }
+    }
+}
+
+/// Attributes of a registered entity
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EntitySchema {
+    pub name: String,
+    pub attributes: Vec<AttributeSchema>,
+}
+
+impl EntitySchema {
+    /// Type found at the path, None when the path doesn't exist on this entity
+    pub fn path_type(&self, path: &AttrPath) -> Option<AttributeType> {
+        self.segments_type(path.segments())
+    }
+
+    pub(crate) fn segments_type(&self, segments: &[PathSegment]) -> Option<AttributeType> {
+        let (PathSegment::Key(root), rest) = segments.split_first()? else {
+            return None;
+        };
+        let root = self.attributes.iter().find(|attr| attr.name == *root)?;
+
+        root.ty.segments_type(rest)
+    }
+}
+
+/// Every entity registered on the Engine, sorted by name
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Schema {
+    pub entities: Vec<EntitySchema>,
+}
+
+impl Schema {
+    /// Get the schema of a single entity
+    pub fn entity(&self, name: &str) -> Option<&EntitySchema> {
+        self.entities.iter().find(|entity| entity.name == name)
+    }
+
+    /// Export as JSON Schema (2020-12), each entity lives under `$defs`
+    #[cfg(feature = "json-schema")]
+    pub fn to_json_schema(&self) -> JsonValue {
+        let defs = self
+            .entities
+            .iter()
+            .map(|entity| (entity.name.clone(), object_json_schema(&entity.attributes)))
+            .collect::<Map<_, _>>();
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$defs": defs,
+        })
+    }
+}
+
+#[cfg(feature = "json-schema")]
+fn object_json_schema(attributes: &[AttributeSchema]) -> JsonValue {
+    let properties = attributes
+        .iter()
+        .map(|attr| (attr.name.clone(), attr.ty.to_json_schema()))
+        .collect::<Map<_, _>>();
+    let required = attributes
+        .iter()
+        .filter(|attr| !matches!(attr.ty, AttributeType::Optional(_)))
+        .map(|attr| attr.name.clone())
+        .collect::<Vec<_>>();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// Rust types that know their attribute type, `#[derive(Entity)]` implements it for nested structs
+pub trait Attribute {
+    fn attribute_type() -> AttributeType;
+}
+
+macro_rules! impl_attribute {
+    ($ty:expr => $($t:ty),*) => {
+        $(
+            impl Attribute for $t {
+                fn attribute_type() -> AttributeType {
+                    $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_attribute!(AttributeType::Bool => bool);
+impl_attribute!(AttributeType::Integer => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_attribute!(AttributeType::Float => f32, f64);
+impl_attribute!(AttributeType::String => String, str, char, Uuid);
+impl_attribute!(AttributeType::Any => Value);
+
+impl<T: Attribute + ?Sized> Attribute for &T {
+    fn attribute_type() -> AttributeType {
+        T::attribute_type()
+    }
+}
+
+impl<T: Attribute + ?Sized> Attribute for Box<T> {
+    fn attribute_type() -> AttributeType {
+        T::attribute_type()
+    }
+}
+
+impl<T: Attribute> Attribute for Option<T> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Optional(Box::new(T::attribute_type()))
+    }
+}
+
+macro_rules! impl_list_attribute {
+    ($($t:ty),*) => {
+        $(
+            impl<T: Attribute> Attribute for $t {
+                fn attribute_type() -> AttributeType {
+                    AttributeType::List(Box::new(T::attribute_type()))
+                }
+            }
+        )*
+    };
+}
+
+impl_list_attribute!(Vec<T>, [T], HashSet<T>, BTreeSet<T>);
+
+impl<T: Attribute, const N: usize> Attribute for [T; N] {
+    fn attribute_type() -> AttributeType {
+        AttributeType::List(Box::new(T::attribute_type()))
+    }
+}
+
+impl<K, V: Attribute> Attribute for HashMap<K, V> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Map(Box::new(V::attribute_type()))
+    }
+}
+
+impl<K, V: Attribute> Attribute for BTreeMap<K, V> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Map(Box::new(V::attribute_type()))
+    }
+}
+
+thread_local! {
+    /// Structs whose type is being built, to stop at recursive ones
+    static EXPANDING: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Used by `#[derive(Entity)]` to build the type of `T` from its fields
+///
+/// A struct nested inside itself, eg. through a `Box<Self>` or `Vec<Self>` field, is `AttributeType::Any` there
+#[doc(hidden)]
+pub fn struct_type<T: ?Sized>(fields: impl FnOnce() -> Vec<AttributeSchema>) -> AttributeType {
+    let name = type_name::<T>();
+    let recursive = EXPANDING.with_borrow_mut(|expanding| match expanding.contains(&name) {
+        true => true,
+        false => {
+            expanding.push(name);
+            false
+        }
+    });
+
+    if recursive {
+        return AttributeType::Any;
+    }
+
+    let fields = fields();
+    EXPANDING.with_borrow_mut(|expanding| expanding.pop());
+
+    AttributeType::Struct(fields)
+}
+
+/// Used by `#[derive(Entity)]` so fields of unknown types fall back to `AttributeType::Any`
+#[doc(hidden)]
+pub struct TypeProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> TypeProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait KnownAttribute {
+    fn probe_type(&self) -> AttributeType;
+}
+
+impl<T: Attribute + ?Sized> KnownAttribute for &TypeProbe<T> {
+    fn probe_type(&self) -> AttributeType {
+        T::attribute_type()
+    }
+}
+
+#[doc(hidden)]
+pub trait UnknownAttribute {
+    fn probe_type(&self) -> AttributeType;
+}
+
+impl<T: ?Sized> UnknownAttribute for TypeProbe<T> {
+    fn probe_type(&self) -> AttributeType {
+        AttributeType::Any
+    }
+}
+
+/// Type of a literal value
+pub(crate) fn literal_type(value: &Value) -> AttributeType {
+    match value {
+        Value::Bool(_) => AttributeType::Bool,
+        Value::U8(_)
+        | Value::U16(_)
+        | Value::U32(_)
+        | Value::U64(_)
+        | Value::I8(_)
+        | Value::I16(_)
+        | Value::I32(_)
+        | Value::I64(_) => AttributeType::Integer,
+        Value::F32(_) | Value::F64(_) => AttributeType::Float,
+        Value::Char(_) | Value::String(_) => AttributeType::String,
+        Value::Option(Some(inner)) | Value::Newtype(inner) => literal_type(inner),
+        Value::Seq(_) => AttributeType::List(Box::new(AttributeType::Any)),
+        Value::Map(_) => AttributeType::Map(Box::new(AttributeType::Any)),
+        _ => AttributeType::Any,
+    }
+}
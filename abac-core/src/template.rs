@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_value::Value;
+
+use crate::{
+    Attribute, AttributeType, Condition, Definition, Error, FunctionCall, Policy, Quantifier, Rule,
+    Rules, SideRule, fits, literal_type, unwrap_optional,
+};
+
+/// Rules with typed placeholders, eg. `subject.level >= $min_level` written as
+/// `{ "left": { "Subject": "level" }, "operator": "GreaterEqual", "right": { "Param": "min_level" } }`
+///
+/// Deserializes from `{ "params": { "min_level": "Integer" }, "rules": [[...]] }`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Template {
+    #[serde(default)]
+    pub(crate) params: BTreeMap<String, AttributeType>,
+    pub(crate) rules: Rules,
+}
+
+/// Values bound to the placeholders of a `Template`, eg. a customer's thresholds
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Params(BTreeMap<String, Value>);
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the value to the placeholder `name`, failing when it can't be serialized
+    #[inline]
+    pub fn bind(mut self, name: impl Into<String>, value: impl Serialize) -> Result<Self, Error> {
+        let value = serde_value::to_value(&value)?;
+        self.0.insert(name.into(), value);
+
+        Ok(self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+}
+
+impl Template {
+    pub fn new(rules: impl Into<Rules>) -> Self {
+        Self {
+            params: BTreeMap::new(),
+            rules: rules.into(),
+        }
+    }
+
+    /// Declare the placeholder `name` holding a `T`
+    #[inline]
+    pub fn param<T: Attribute>(self, name: impl Into<String>) -> Self {
+        self.param_type(name, T::attribute_type())
+    }
+
+    /// Declare the placeholder `name` holding a value of `ty`
+    #[inline]
+    pub fn param_type(mut self, name: impl Into<String>, ty: AttributeType) -> Self {
+        self.params.insert(name.into(), ty);
+
+        self
+    }
+
+    pub fn params(&self) -> &BTreeMap<String, AttributeType> {
+        &self.params
+    }
+
+    /// Every placeholder used by the rules must be declared
+    pub fn check(&self) -> Result<(), Error> {
+        bind_rules(&self.rules, &|name| match self.params.contains_key(name) {
+            true => Ok(SideRule::Param(name.to_string())),
+            false => Err(Error::UnknownParameter(name.to_string())),
+        })
+        .map(|_| ())
+    }
+
+    /// Concrete rules with every placeholder replaced by its value
+    ///
+    /// Every declared placeholder must be bound to a value of its type and nothing else may be bound
+    pub fn instantiate(&self, params: &Params) -> Result<Rules, Error> {
+        if let Some(name) = params
+            .0
+            .keys()
+            .find(|name| !self.params.contains_key(*name))
+        {
+            return Err(Error::UnknownParameter(name.clone()));
+        }
+
+        for (name, ty) in &self.params {
+            let value = params
+                .get(name)
+                .ok_or_else(|| Error::UnboundParameter(name.clone()))?;
+            let actual = literal_type(value);
+
+            if !bindable(ty, &actual) {
+                return Err(Error::InvalidParameter {
+                    name: name.clone(),
+                    reason: format!("expects {ty:?}, got {actual:?}"),
+                });
+            }
+        }
+
+        bind_rules(&self.rules, &|name| match params.get(name) {
+            Some(value) => Ok(SideRule::Literal(value.clone())),
+            None => Err(Error::UnknownParameter(name.to_string())),
+        })
+    }
+}
+
+/// Integers are accepted where floats are expected, eg. a threshold of `3` for a `f64`
+fn bindable(expected: &AttributeType, actual: &AttributeType) -> bool {
+    match (unwrap_optional(expected), actual) {
+        (AttributeType::Float, AttributeType::Integer) => true,
+        _ => fits(expected, actual),
+    }
+}
+
+/// Rebuild the rules with every placeholder replaced by `param`
+fn bind_rules(
+    rules: &Rules,
+    param: &dyn Fn(&str) -> Result<SideRule, Error>,
+) -> Result<Rules, Error> {
+    let groups = rules
+        .0
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .map(|condition| bind_condition(condition, param))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Rules(groups))
+}
+
+fn bind_condition(
+    condition: &Condition,
+    param: &dyn Fn(&str) -> Result<SideRule, Error>,
+) -> Result<Condition, Error> {
+    let quantifier = |quantifier: &Quantifier| -> Result<Quantifier, Error> {
+        Ok(Quantifier {
+            collection: bind_side(&quantifier.collection, param)?,
+            rules: bind_rules(&quantifier.rules, param)?,
+        })
+    };
+
+    let condition = match condition {
+        // Bound literals may need parsing, eg. CIDR blocks
+        Condition::Compare(rule) => Condition::Compare(Rule::new(
+            bind_side(&rule.left, param)?,
+            rule.operator.clone(),
+            bind_side(&rule.right, param)?,
+        )?),
+        Condition::Any(any) => Condition::Any(quantifier(any)?),
+        Condition::All(all) => Condition::All(quantifier(all)?),
+        Condition::Inlined(definition) => Condition::Inlined(Definition {
+            name: definition.name.clone(),
+            rules: bind_rules(&definition.rules, param)?,
+        }),
+        Condition::Included(policy) => Condition::Included(Policy {
+            id: policy.id.clone(),
+            rules: bind_rules(&policy.rules, param)?,
+        }),
+        condition => condition.clone(),
+    };
+
+    Ok(condition)
+}
+
+fn bind_side(
+    side: &SideRule,
+    param: &dyn Fn(&str) -> Result<SideRule, Error>,
+) -> Result<SideRule, Error> {
+    let side = match side {
+        SideRule::Param(name) => param(name)?,
+        SideRule::Function(call) => SideRule::Function(FunctionCall {
+            name: call.name.clone(),
+            args: call
+                .args
+                .iter()
+                .map(|arg| bind_side(arg, param))
+                .collect::<Result<_, _>>()?,
+        }),
+        SideRule::Arithmetic(expression) => {
+            let mut expression = expression.clone();
+            expression.left = bind_side(&expression.left, param)?;
+            expression.right = bind_side(&expression.right, param)?;
+
+            SideRule::Arithmetic(expression)
+        }
+        side => side.clone(),
+    };
+
+    Ok(side)
+}
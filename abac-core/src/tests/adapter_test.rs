@@ -4,7 +4,7 @@ use macros::Entity;
 use serde_value::Value;
 use uuid::Uuid;
 
-use crate::{DynAdapter, EntityAdapter, LoadResult};
+use crate::{DynAdapter, EntityAdapter, Error, LoadResult};
 
 #[derive(Entity, Default)]
 struct Task {
@@ -13,9 +13,10 @@ struct Task {
 
 impl EntityAdapter for Task {
     type Provider = PathBuf;
+    type Id = Uuid;
 
     // Assume it loads data from database
-    fn load_data(_: uuid::Uuid, _: &Self::Provider) -> LoadResult<Self> {
+    fn load_data(_: uuid::Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
         Box::pin(async move {
             Ok(Self {
                 owner: "WiszeL".into(),
@@ -32,7 +33,10 @@ async fn dyn_adapter_test() {
 
     // ##### Act ##### //
     let adapter_provider = dyn_adapter.provider_type();
-    let adapter_load = dyn_adapter.load(Uuid::nil(), &path_buf).await.unwrap();
+    let adapter_load = dyn_adapter
+        .load(Uuid::nil().into(), &path_buf)
+        .await
+        .unwrap();
 
     // ##### Arrange ##### //
     assert_eq!(
@@ -46,3 +50,51 @@ async fn dyn_adapter_test() {
         "Should really load"
     );
 }
+
+#[derive(Debug, thiserror::Error)]
+#[error("Project not found")]
+struct NotFound;
+
+async fn load_project(id: Uuid, _: &PathBuf) -> Result<Project, NotFound> {
+    if id.is_nil() {
+        return Err(NotFound);
+    }
+
+    Ok(Project {
+        name: "abac-rs".into(),
+    })
+}
+
+#[derive(Entity, EntityAdapter, Default)]
+#[abac(provider = PathBuf, load = load_project)]
+struct Project {
+    name: String,
+}
+
+#[tokio::test]
+async fn derive_adapter_test() {
+    // ##### Arrange ##### //
+    let dyn_adapter = Project::default();
+    let path_buf = PathBuf::new();
+
+    // ##### Act ##### //
+    let adapter_provider = dyn_adapter.provider_type();
+    let found = dyn_adapter.load(Uuid::max().into(), &path_buf).await;
+    let not_found = dyn_adapter.load(Uuid::nil().into(), &path_buf).await;
+
+    // ##### Assert ##### //
+    assert_eq!(
+        adapter_provider,
+        path_buf.type_id(),
+        "Should have the same provider!"
+    );
+    assert_eq!(
+        found.unwrap().get_attribute("name").unwrap().as_deref(),
+        Some(&Value::String("abac-rs".into())),
+        "Should load through the given function"
+    );
+    assert!(
+        matches!(not_found, Err(Error::LoadError(_))),
+        "Error should be converted into LoadError"
+    );
+}
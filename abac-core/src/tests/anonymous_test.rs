@@ -0,0 +1,99 @@
+use super::common::{Task, User, engine, task, user};
+use crate::{Engine, Entity, Error, EvaluateEntity, Rules, object, subject};
+
+/// Guest kept in memory, with attributes a loaded user doesn't have
+#[derive(Entity, Default)]
+struct Guest {
+    authenticated: bool,
+    visits: u32,
+}
+
+fn rules() -> Rules {
+    Rules::from(subject(User::AUTHENTICATED).eq(false)).and(object(Task::PUBLIC).eq(true))
+}
+
+#[tokio::test]
+async fn subject_required_test() {
+    // ##### Arrange ##### //
+    let required = engine();
+    let optional = engine().with_subject_required(false);
+
+    // ##### Act ##### //
+    let required_result = required
+        .evaluate(EvaluateEntity::new("user", None), task(), &rules())
+        .await;
+    let optional_result = optional
+        .evaluate(
+            EvaluateEntity::new("user", None),
+            task(),
+            &Rules::from(object(Task::PUBLIC).eq(true)),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(required_result, Err(Error::SubjectNotFound)),
+        "Subject without id should fail by default"
+    );
+    assert!(
+        optional_result.unwrap(),
+        "Subject without id should be allowed when not required"
+    );
+}
+
+#[tokio::test]
+async fn anonymous_test() {
+    // ##### Arrange ##### //
+    let engine = engine().with_anonymous("guest", User::default());
+
+    // ##### Act ##### //
+    let guest = engine
+        .evaluate(engine.anonymous().unwrap(), task(), &rules())
+        .await;
+    let authenticated = engine.evaluate(user(), task(), &rules()).await;
+    let schema = engine.schema();
+
+    // ##### Assert ##### //
+    assert!(guest.unwrap(), "Guest should be permitted on public task");
+    assert!(
+        !authenticated.unwrap(),
+        "Authenticated user shouldn't match the guest rules"
+    );
+    assert!(
+        schema.entities.iter().any(|entity| entity.name == "guest"),
+        "Anonymous principal should be described by the schema"
+    );
+    assert!(
+        matches!(Engine::new().anonymous(), Err(Error::AnonymousNotFound)),
+        "Anonymous principal should be registered explicitly"
+    );
+}
+
+#[tokio::test]
+async fn anonymous_clash_test() {
+    // ##### Arrange ##### //
+    let engine = engine().with_anonymous(
+        "user",
+        Guest {
+            authenticated: false,
+            visits: 3,
+        },
+    );
+
+    // ##### Act ##### //
+    let guest = engine
+        .evaluate(engine.anonymous().unwrap(), task(), &rules())
+        .await;
+    let fields = engine.get_entity_fields("user");
+
+    // ##### Assert ##### //
+    assert!(
+        guest.unwrap(),
+        "Guest user should be permitted on public task"
+    );
+    assert_eq!(
+        fields.unwrap(),
+        ["name", "tenant", "level", "authenticated"],
+        "Registered user should keep its schema"
+    );
+}
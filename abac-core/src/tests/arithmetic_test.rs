@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    ArithmeticOperator, Engine, Entity, EntityAdapter, Error, Expression, LoadResult, Operator,
+    Rule, Rules, SideRule, evaluate, object, subject,
+};
+
+#[derive(Entity, Default)]
+struct Account {
+    approval_limit: u64,
+    used: u64,
+    size: u64,
+    quota: u64,
+    amount: u64,
+    balance: i64,
+    rate: f64,
+    name: String,
+}
+
+impl EntityAdapter for Account {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+fn account() -> Account {
+    Account {
+        approval_limit: 500,
+        used: 70,
+        size: 30,
+        quota: 100,
+        amount: 1_000,
+        balance: -20,
+        rate: 0.5,
+        name: "WiszeL".into(),
+    }
+}
+
+fn arithmetic(left: SideRule, operator: ArithmeticOperator, right: SideRule) -> SideRule {
+    SideRule::Arithmetic(Box::new(Expression {
+        left,
+        operator,
+        right,
+    }))
+}
+
+fn literal(value: Value) -> SideRule {
+    SideRule::Literal(value)
+}
+
+#[test]
+fn evaluate_arithmetic_test() {
+    // ##### Arrange ##### //
+    let account = account();
+
+    let approval =
+        Rules::new().and(object(Account::AMOUNT).le(subject(Account::APPROVAL_LIMIT) * 2u64));
+    let quota = Rules::new()
+        .and((object(Account::USED) + subject(Account::SIZE)).le(object(Account::QUOTA)));
+    let over_quota = Rules::new()
+        .and((object(Account::USED) + subject(Account::SIZE) + 1u64).le(object(Account::QUOTA)));
+    let signed = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("balance".parse().unwrap()),
+            ArithmeticOperator::Subtract,
+            SideRule::Subject("used".parse().unwrap()),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::I64(-90)),
+    });
+    let float = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("amount".parse().unwrap()),
+            ArithmeticOperator::Multiply,
+            SideRule::Subject("rate".parse().unwrap()),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::F64(500.0)),
+    });
+    let remainder = Rules::new().and((subject(Account::AMOUNT) % 7u64).eq(6u64));
+
+    // ##### Act & Assert ##### //
+    for (name, rules, expected) in [
+        ("approval", approval, true),
+        ("quota", quota, true),
+        ("over quota", over_quota, false),
+        ("signed", signed, true),
+        ("float", float, true),
+        ("remainder", remainder, true),
+    ] {
+        assert_eq!(
+            evaluate(&account, &account, &rules).unwrap(),
+            expected,
+            "`{name}` should evaluate to {expected}"
+        );
+    }
+}
+
+#[test]
+fn evaluate_arithmetic_error_test() {
+    // ##### Arrange ##### //
+    let zero = Account {
+        quota: 0,
+        ..account()
+    };
+    let max = Account {
+        amount: u64::MAX,
+        ..account()
+    };
+
+    let divide = Rules::new().and((subject(Account::USED) / object(Account::QUOTA)).eq(0u64));
+    let remainder = Rules::new().and((subject(Account::USED) % object(Account::QUOTA)).eq(0u64));
+    let divide_float = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("rate".parse().unwrap()),
+            ArithmeticOperator::Divide,
+            SideRule::Object("quota".parse().unwrap()),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::F64(0.0)),
+    });
+    let overflow = Rules::new().and((subject(Account::AMOUNT) * 2u64).gt(0u64));
+    let not_number = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("name".parse().unwrap()),
+            ArithmeticOperator::Add,
+            literal(Value::U64(1)),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::U64(1)),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        matches!(evaluate(&zero, &zero, &divide), Err(Error::DivisionByZero)),
+        "Division by zero should fail"
+    );
+    assert!(
+        matches!(
+            evaluate(&zero, &zero, &remainder),
+            Err(Error::DivisionByZero)
+        ),
+        "Remainder by zero should fail"
+    );
+    assert!(
+        matches!(
+            evaluate(&zero, &zero, &divide_float),
+            Err(Error::DivisionByZero)
+        ),
+        "Float division by zero should fail"
+    );
+    assert!(
+        matches!(
+            evaluate(&max, &max, &overflow),
+            Err(Error::ArithmeticOverflow(_))
+        ),
+        "Overflow should fail"
+    );
+    assert!(
+        matches!(
+            evaluate(&zero, &zero, &not_number),
+            Err(Error::InvalidOperands { .. })
+        ),
+        "String isn't a number"
+    );
+}
+
+#[test]
+fn arithmetic_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"{
+        "left": { "Object": "amount" },
+        "operator": "LessEqual",
+        "right": {
+            "Arithmetic": {
+                "left": { "Subject": "approval_limit" },
+                "operator": "Multiply",
+                "right": { "Literal": 2 }
+            }
+        }
+    }"#;
+
+    // ##### Act ##### //
+    let rule: Rule = serde_json::from_str(json).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        rule,
+        Rule {
+            left: SideRule::Object("amount".parse().unwrap()),
+            operator: Operator::LessEqual,
+            right: arithmetic(
+                SideRule::Subject("approval_limit".parse().unwrap()),
+                ArithmeticOperator::Multiply,
+                literal(Value::U64(2)),
+            ),
+        },
+        "Arithmetic should deserialize from its operands and operator"
+    );
+    assert!(
+        evaluate(&account(), &account(), &rule.into()).unwrap(),
+        "Deserialized arithmetic should evaluate"
+    );
+}
+
+#[test]
+fn validate_arithmetic_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<Account>("account");
+
+    let valid = Rules::new().and((subject(Account::USED) + object(Account::SIZE)).le(100u64));
+    let not_number = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("name".parse().unwrap()),
+            ArithmeticOperator::Add,
+            literal(Value::U64(1)),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::U64(1)),
+    });
+    let unknown = Rules::new().and(Rule {
+        left: arithmetic(
+            SideRule::Subject("limit".parse().unwrap()),
+            ArithmeticOperator::Add,
+            literal(Value::U64(1)),
+        ),
+        operator: Operator::Equal,
+        right: literal(Value::U64(1)),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("account", "account", &valid).is_ok(),
+        "Numbers should be valid operands"
+    );
+    assert!(
+        matches!(
+            engine.validate("account", "account", &not_number),
+            Err(Error::InvalidOperands { ref operator, .. }) if operator == "Add"
+        ),
+        "String isn't a valid operand"
+    );
+    assert!(
+        matches!(
+            engine.validate("account", "account", &unknown),
+            Err(Error::UnknownAttribute { ref path, .. }) if path == "limit"
+        ),
+        "Unknown path inside arithmetic should be invalid"
+    );
+}
@@ -0,0 +1,98 @@
+use serde::{Serialize, Serializer, ser::Error as _};
+use serde_value::Value;
+
+use crate::{Entity, Error, Operand, Operator, Rule, Rules, SideRule, evaluate, object, subject};
+
+#[derive(Entity)]
+struct User {
+    name: String,
+    age: u64,
+}
+
+#[derive(Entity)]
+struct Task {
+    owner: String,
+}
+
+#[derive(Serialize)]
+enum Status {
+    Open,
+}
+
+#[derive(Entity)]
+struct Ticket {
+    status: Status,
+}
+
+/// Value whose serialization always fails
+struct Broken;
+
+impl Serialize for Broken {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom("broken"))
+    }
+}
+
+#[test]
+fn attr_constant_test() {
+    // ##### Act & Assert ##### //
+    assert_eq!(User::NAME.name(), "name", "Should refer to field 'name'");
+    assert_eq!(User::AGE.name(), "age", "Should refer to field 'age'");
+    assert_eq!(Task::OWNER.name(), "owner", "Should refer to field 'owner'");
+}
+
+#[test]
+fn builder_test() {
+    // ##### Arrange ##### //
+    let user = User {
+        name: "WiszeL".into(),
+        age: 21,
+    };
+    let task = Task {
+        owner: "WiszeL".into(),
+    };
+
+    // ##### Act ##### //
+    let adult = subject(User::AGE).ge(18u64);
+    let rules = Rules::new().and(adult.clone()).and_any([
+        subject(User::AGE).gt(30u64),
+        subject(User::NAME).eq(object(Task::OWNER)),
+    ]);
+
+    // ##### Assert ##### //
+    assert_eq!(
+        adult,
+        Rule {
+            left: SideRule::Subject("age".parse().unwrap()),
+            operator: Operator::GreaterEqual,
+            right: SideRule::Literal(Value::U64(18)),
+        },
+        "Should build the same rule as written by hand"
+    );
+    assert!(
+        evaluate(&user, &task, &rules).unwrap(),
+        "Built rules should pass"
+    );
+}
+
+#[test]
+fn literal_test() {
+    // ##### Arrange ##### //
+    let ticket = Ticket {
+        status: Status::Open,
+    };
+
+    // ##### Act ##### //
+    let open = Operand::literal(&Status::Open).map(|status| object(Ticket::STATUS).eq(status));
+    let broken = Operand::literal(&Broken);
+
+    // ##### Assert ##### //
+    assert!(
+        evaluate(&ticket, &ticket, &open.unwrap().into()).unwrap(),
+        "Serializable literal should be compared"
+    );
+    assert!(
+        matches!(broken, Err(Error::SerializationError(_))),
+        "Failing literal should be an error instead of a panic"
+    );
+}
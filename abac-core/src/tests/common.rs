@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use crate::{Engine, Entity, EntityAdapter, EvaluateEntity, LoadResult};
+
+#[derive(Entity, Default)]
+pub(super) struct User {
+    pub(super) name: String,
+    pub(super) tenant: String,
+    pub(super) level: u8,
+    pub(super) authenticated: bool,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                name: "WiszeL".into(),
+                tenant: "acme".into(),
+                level: 3,
+                authenticated: true,
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+pub(super) struct Task {
+    pub(super) owner: String,
+    pub(super) tenant: String,
+    pub(super) level: u8,
+    pub(super) public: bool,
+}
+
+impl EntityAdapter for Task {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                owner: "Someone".into(),
+                tenant: "acme".into(),
+                level: 2,
+                public: true,
+            })
+        })
+    }
+}
+
+/// Engine loading `user` and `task` from the database
+pub(super) fn engine() -> Engine {
+    Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Task>("task")
+}
+
+/// User loaded by the `engine`
+pub(super) fn user() -> EvaluateEntity<'static> {
+    EvaluateEntity::new("user", Some(Uuid::nil()))
+}
+
+/// Task loaded by the `engine`
+pub(super) fn task() -> EvaluateEntity<'static> {
+    EvaluateEntity::new("task", Some(Uuid::nil()))
+}
@@ -0,0 +1,180 @@
+use super::common::{self, Task, User, task, user};
+use crate::{
+    Condition, Definition, Definitions, Engine, Error, Rules, evaluate, named, object, subject,
+};
+
+fn definitions() -> Definitions {
+    Definitions::new()
+        .define("is_owner", subject(User::NAME).eq(object(Task::OWNER)))
+        .define(
+            "same_tenant",
+            subject(User::TENANT).eq(object(Task::TENANT)),
+        )
+        .define(
+            "can_edit",
+            Rules::new()
+                .and(named("same_tenant"))
+                .and(named("is_owner")),
+        )
+}
+
+fn engine() -> Engine {
+    common::engine().with_definitions(definitions())
+}
+
+async fn evaluate_named(engine: &Engine, rules: &Rules) -> Result<bool, Error> {
+    engine.evaluate(user(), task(), rules).await
+}
+
+#[tokio::test]
+async fn evaluate_named_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+
+    // ##### Act ##### //
+    let same_tenant = evaluate_named(&engine, &named("same_tenant").into()).await;
+    let can_edit = evaluate_named(&engine, &named("can_edit").into()).await;
+    let can_view = evaluate_named(
+        &engine,
+        &Rules::new().and_any([named("is_owner"), named("same_tenant")]),
+    )
+    .await;
+
+    // ##### Assert ##### //
+    assert!(same_tenant.unwrap(), "Same tenant should pass");
+    assert!(
+        !can_edit.unwrap(),
+        "Nested named condition should fail for non owner"
+    );
+    assert!(can_view.unwrap(), "Any of the named conditions should pass");
+}
+
+#[tokio::test]
+async fn named_error_test() {
+    // ##### Arrange ##### //
+    let cycle_engine = engine()
+        .define("a", named("b"))
+        .define("b", Rules::new().and(named("same_tenant")).and(named("a")));
+
+    // ##### Act ##### //
+    let cycle = evaluate_named(&cycle_engine, &named("a").into()).await;
+    // A cycle makes every evaluation of the engine fail, not only the ones reaching it
+    let unknown = evaluate_named(&engine(), &named("is_admin").into()).await;
+    let without_engine = evaluate(
+        &User::default(),
+        &Task::default(),
+        &named("is_owner").into(),
+    );
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(cycle, Err(Error::ConditionCycle(ref cycle)) if cycle == "a -> b -> a"),
+        "Cycle should be reported along with its path"
+    );
+    assert!(
+        matches!(unknown, Err(Error::UnknownCondition(ref name)) if name == "is_admin"),
+        "Undefined condition should fail"
+    );
+    assert!(
+        matches!(without_engine, Err(Error::UnknownCondition(_))),
+        "Named condition should need the engine"
+    );
+}
+
+#[test]
+fn check_test() {
+    // ##### Arrange ##### //
+    let cycle = definitions().define("loop", named("loop"));
+    let dangling = definitions().define("can_delete", named("is_admin"));
+
+    // ##### Act & Assert ##### //
+    assert!(definitions().check().is_ok(), "Definitions should be valid");
+    assert!(
+        matches!(cycle.check(), Err(Error::ConditionCycle(ref cycle)) if cycle == "loop -> loop"),
+        "Self reference should be a cycle"
+    );
+    assert!(
+        matches!(dangling.check(), Err(Error::UnknownCondition(ref name)) if name == "is_admin"),
+        "Dangling reference should be reported"
+    );
+}
+
+#[test]
+fn inline_test() {
+    // ##### Act ##### //
+    let inlined = definitions().inline(&named("can_edit").into()).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        inlined,
+        Rules::from(Condition::Inlined(Definition {
+            name: "can_edit".into(),
+            rules: Rules::new()
+                .and(Condition::Inlined(Definition {
+                    name: "same_tenant".into(),
+                    rules: subject(User::TENANT).eq(object(Task::TENANT)).into(),
+                }))
+                .and(Condition::Inlined(Definition {
+                    name: "is_owner".into(),
+                    rules: subject(User::NAME).eq(object(Task::OWNER)).into(),
+                })),
+        })),
+        "Named conditions should be replaced by their rules, keeping their names"
+    );
+}
+
+#[test]
+fn definitions_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"{
+        "is_owner": [[{ "left": { "Subject": "name" }, "operator": "Equal", "right": { "Object": "owner" } }]],
+        "can_edit": [[{ "Named": "is_owner" }]]
+    }"#;
+
+    // ##### Act ##### //
+    let definitions: Definitions = serde_json::from_str(json).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        definitions.get("can_edit"),
+        Some(&named("is_owner").into()),
+        "Should reference the other definition by name"
+    );
+    assert!(definitions.check().is_ok(), "Definitions should be valid");
+}
+
+#[test]
+fn validate_named_test() {
+    // ##### Arrange ##### //
+    let engine = engine().define("is_manager", subject(User::NAME).eq(object(Task::OWNER)));
+    let invalid = engine.define(
+        "is_assignee",
+        Rules::from(crate::Rule {
+            left: crate::SideRule::Object("assignee".parse().unwrap()),
+            operator: crate::Operator::Equal,
+            right: crate::SideRule::Subject("name".parse().unwrap()),
+        }),
+    );
+
+    // ##### Act & Assert ##### //
+    assert!(
+        invalid
+            .validate("user", "task", &named("can_edit").into())
+            .is_ok(),
+        "Nested named conditions should be valid"
+    );
+    assert!(
+        matches!(
+            invalid.validate("user", "task", &named("is_assignee").into()),
+            Err(Error::UnknownAttribute { ref path, .. }) if path == "assignee"
+        ),
+        "Paths inside named conditions should be checked"
+    );
+    assert!(
+        matches!(
+            invalid.validate("user", "task", &named("is_admin").into()),
+            Err(Error::UnknownCondition(_))
+        ),
+        "Undefined condition should be invalid"
+    );
+}
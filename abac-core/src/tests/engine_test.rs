@@ -1,10 +1,12 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Mutex};
 
 use macros::Entity;
 use serde_value::Value;
 use uuid::Uuid;
 
-use crate::{Engine, EntityAdapter, EvaluateEntity, LoadResult, Operator, Rule, Rules, SideRule};
+use crate::{
+    Engine, EntityAdapter, Error, EvaluateEntity, LoadResult, Operator, Rule, Rules, SideRule,
+};
 
 #[derive(Entity, Default)]
 struct Task {
@@ -13,9 +15,10 @@ struct Task {
 
 impl EntityAdapter for Task {
     type Provider = PathBuf;
+    type Id = Uuid;
 
     // Assume it loads data from database
-    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<Self> {
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
         Box::pin(async move {
             Ok(Self {
                 owner: "WiszeL".into(),
@@ -31,9 +34,10 @@ struct User {
 
 impl EntityAdapter for User {
     type Provider = PathBuf;
+    type Id = Uuid;
 
     // Assume it loads data from database
-    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<Self> {
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
         Box::pin(async move {
             Ok(Self {
                 name: "WiszeL".into(),
@@ -74,16 +78,22 @@ async fn evaluate_with_subject_test() {
     // ##### Arrange ##### //
     let path_buf = PathBuf::new();
 
-    let w_rsc_rule = vec![Rule {
-        left: SideRule::Subject("name".into()),
-        operator: Operator::Equal,
-        right: SideRule::Object("owner".into()),
-    }];
-    let wo_rsc_rule = vec![Rule {
-        left: SideRule::Subject("name".into()),
-        operator: Operator::Equal,
-        right: SideRule::Literal(Value::String("WiszeL".into())),
-    }];
+    let w_rsc_rule = vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Object("owner".parse().unwrap()),
+        }
+        .into(),
+    ];
+    let wo_rsc_rule = vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("WiszeL".into())),
+        }
+        .into(),
+    ];
 
     let w_rsc_rules = Rules(vec![w_rsc_rule]);
     let wo_rsc_rules = Rules(vec![wo_rsc_rule]);
@@ -94,16 +104,16 @@ async fn evaluate_with_subject_test() {
         .register_adapter::<Task>("task");
 
     // ##### Act ##### //
-    let evaluate_subject = EvaluateEntity::new("user", Uuid::nil().into());
+    let evaluate_subject = EvaluateEntity::new("user", Some(Uuid::nil()));
 
     // 1. With Resource
-    let evaluate_resource = EvaluateEntity::new("task", Uuid::nil().into());
+    let evaluate_resource = EvaluateEntity::new("task", Some(Uuid::nil()));
     let w_result = engine
         .evaluate(evaluate_subject.clone(), evaluate_resource, &w_rsc_rules)
         .await;
 
     // 2. Without Resource
-    let evaluate_resource = EvaluateEntity::new("task", Uuid::nil().into());
+    let evaluate_resource = EvaluateEntity::new("task", Some(Uuid::nil()));
     let wo_result = engine
         .evaluate(evaluate_subject, evaluate_resource, &wo_rsc_rules)
         .await;
@@ -117,3 +127,145 @@ async fn evaluate_with_subject_test() {
     assert!(wo_result.is_ok(), "Evalute shouldn't throw any error!");
     assert!(wo_result.unwrap(), "Evaluate should be true!");
 }
+
+#[test]
+fn validate_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .register_adapter::<User>("user")
+        .register_adapter::<Task>("task");
+
+    let valid_rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Object("owner".parse().unwrap()),
+        }
+        .into(),
+    ]]);
+    let invalid_rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Object("owner.name".parse().unwrap()),
+        }
+        .into(),
+    ]]);
+
+    // ##### Act ##### //
+    let valid = engine.validate("user", "task", &valid_rules);
+    let invalid = engine.validate("user", "task", &invalid_rules);
+    let unknown = engine.validate("user", "unknown", &invalid_rules);
+
+    // ##### Assert ##### //
+    assert!(valid.is_ok(), "Existing paths should be valid");
+    assert!(
+        matches!(invalid, Err(Error::UnknownAttribute { ref entity, ref path }) if entity == "task" && path == "owner.name"),
+        "Path into a string should be invalid"
+    );
+    assert!(unknown.is_ok(), "Unknown schema should be skipped");
+}
+
+/// Assume it's a database, remembering which columns were selected
+#[derive(Default)]
+struct Recorder {
+    selected: Mutex<Vec<Vec<String>>>,
+    full_loads: Mutex<usize>,
+}
+
+#[derive(Entity, Default)]
+struct Ticket {
+    owner: String,
+    attachment: Vec<u8>,
+}
+
+impl EntityAdapter for Ticket {
+    type Provider = Recorder;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, recorder: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            *recorder.full_loads.lock().unwrap() += 1;
+
+            Ok(Self {
+                owner: "WiszeL".into(),
+                attachment: vec![0; 1024],
+            })
+        })
+    }
+
+    // Assume it only selects the needed columns
+    fn load_fields<'a>(
+        _: Uuid,
+        fields: &'a [&'a str],
+        recorder: &'a Self::Provider,
+    ) -> LoadResult<'a, Self> {
+        Box::pin(async move {
+            recorder
+                .selected
+                .lock()
+                .unwrap()
+                .push(fields.iter().map(ToString::to_string).collect());
+
+            Ok(Self {
+                owner: "WiszeL".into(),
+                ..Default::default()
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn load_fields_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(Recorder::default())
+        .register_adapter::<Ticket>("ticket")
+        .with_subject_required(false);
+    let rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Object("owner".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("WiszeL".into())),
+        }
+        .into(),
+    ]]);
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("ticket", None),
+            EvaluateEntity::new("ticket", Some(Uuid::nil())),
+            &rules,
+        )
+        .await;
+    let exists = engine
+        .evaluate(
+            EvaluateEntity::new("ticket", None),
+            EvaluateEntity::new("ticket", Some(Uuid::nil())),
+            &Rules::new(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(result.unwrap(), "Evaluate should be true!");
+    assert!(exists.unwrap(), "Empty rules should pass");
+
+    let recorder = engine
+        .providers
+        .values()
+        .next()
+        .unwrap()
+        .downcast_ref::<Recorder>()
+        .unwrap();
+    assert_eq!(
+        *recorder.selected.lock().unwrap(),
+        vec![vec!["owner".to_string()]],
+        "Should only ask for the fields used by the rules"
+    );
+    assert_eq!(
+        *recorder.full_loads.lock().unwrap(),
+        1,
+        "Resource nothing is read from should be loaded whole, not with no fields"
+    );
+}
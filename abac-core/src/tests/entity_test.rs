@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Serializer};
+use serde_value::Value;
+
+use crate::{AttrPath, Entity, Error};
+
+#[derive(Entity)]
+struct Document {
+    owner: String,
+    pages: u64,
+    blob: Vec<u8>,
+}
+
+#[test]
+fn get_attribute_test() {
+    // ##### Arrange ##### //
+    let document = Document {
+        owner: "WiszeL".into(),
+        pages: 3,
+        blob: vec![0; 1024],
+    };
+
+    let mut map = HashMap::new();
+    map.insert("owner".to_string(), Value::String("WiszeL".into()));
+
+    // ##### Act ##### //
+    let owner = document.get_attribute("owner").unwrap();
+    let pages = document.get_attribute("pages").unwrap();
+    let missing = document.get_attribute("editor").unwrap();
+    let map_owner = map.get_attribute("owner").unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        owner.as_deref(),
+        Some(&Value::String("WiszeL".into())),
+        "Should get the owner field"
+    );
+    assert_eq!(
+        pages.as_deref(),
+        Some(&Value::U64(3)),
+        "Should get the pages field"
+    );
+    assert!(missing.is_none(), "Unknown field should be None");
+    assert_eq!(
+        map_owner.as_deref(),
+        Some(&Value::String("WiszeL".into())),
+        "Map entity should borrow the value"
+    );
+    assert_eq!(
+        document.to_value().unwrap().len(),
+        3,
+        "Full snapshot should still be available"
+    );
+}
+
+/// Assume it's a value that can't be serialized, eg. a lock that is poisoned
+struct Poisoned;
+
+impl Serialize for Poisoned {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("poisoned"))
+    }
+}
+
+#[derive(Entity)]
+struct Session {
+    token: Poisoned,
+}
+
+#[test]
+fn serialization_error_test() {
+    // ##### Arrange ##### //
+    let session = Session { token: Poisoned };
+    let path = AttrPath::parse("token").unwrap();
+
+    // ##### Act ##### //
+    let attribute = session.get_attribute("token");
+    let resolved = path.resolve(&session);
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(attribute, Err(Error::SerializationError(_))),
+        "Serialization error shouldn't be a missing attribute"
+    );
+    assert!(
+        matches!(resolved, Err(Error::SerializationError(_))),
+        "Serialization error should reach the evaluation"
+    );
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use macros::Entity;
 use serde_value::Value;
 
-use crate::{Operator, Rule, Rules, SideRule, evaluate, which_to_evaluate};
+use crate::{Context, Operator, Rule, Rules, SideRule, evaluate, which_to_evaluate};
 
 #[test]
 fn which_to_evaluate_test() {
@@ -16,26 +16,27 @@ fn which_to_evaluate_test() {
     object.insert("owner".to_string(), Value::String("WiszeL".into()));
 
     let literal = Value::Bool(true);
+    let ctx = Context::new(&subject, &object);
 
     // ##### Act & Assert ##### //
 
     /* -----------------------------------------------
      * Case 01 – Subject field exists
      * ----------------------------------------------- */
-    let binding = SideRule::Subject("age".into());
-    let result = which_to_evaluate(&subject, &object, &binding);
+    let binding = SideRule::Subject("age".parse().unwrap());
+    let result = which_to_evaluate(&ctx, &binding).unwrap();
     assert!(
-        matches!(result, Value::I32(21)),
+        matches!(result.as_ref(), Value::I32(21)),
         "Case 01: should return subject field 'age'"
     );
 
     /* -----------------------------------------------
      * Case 02 – Object field exists
      * ----------------------------------------------- */
-    let binding = SideRule::Object("owner".into());
-    let result = which_to_evaluate(&subject, &object, &binding);
+    let binding = SideRule::Object("owner".parse().unwrap());
+    let result = which_to_evaluate(&ctx, &binding).unwrap();
     assert!(
-        matches!(result, Value::String(s) if s == "WiszeL"),
+        matches!(result.as_ref(), Value::String(s) if s == "WiszeL"),
         "Case 02: should return object field 'owner'"
     );
 
@@ -43,19 +44,19 @@ fn which_to_evaluate_test() {
      * Case 03 – Literal value returned directly
      * ----------------------------------------------- */
     let binding = SideRule::Literal(literal.clone());
-    let result = which_to_evaluate(&subject, &object, &binding);
+    let result = which_to_evaluate(&ctx, &binding).unwrap();
     assert!(
-        matches!(result, val if *val == literal),
+        matches!(result.as_ref(), val if *val == literal),
         "Case 03: should return literal directly"
     );
 
     /* -----------------------------------------------
      * Case 04 – Missing field returns false
      * ----------------------------------------------- */
-    let binding = SideRule::Subject("not_found".into());
-    let result = which_to_evaluate(&subject, &object, &binding);
+    let binding = SideRule::Subject("not_found".parse().unwrap());
+    let result = which_to_evaluate(&ctx, &binding).unwrap();
     assert!(
-        matches!(result, Value::Bool(false)),
+        matches!(result.as_ref(), Value::Bool(false)),
         "Case 04: should return error for missing subject field"
     );
 }
@@ -87,11 +88,14 @@ async fn evaluate_test() {
     /* -----------------------------------------------
      * Case 01 – Subject.age >= 18 → true
      * ----------------------------------------------- */
-    let rules = Rules(vec![vec![Rule {
-        left: SideRule::Subject("age".into()),
-        operator: Operator::GreaterEqual,
-        right: SideRule::Literal(Value::U64(18)),
-    }]]);
+    let rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("age".parse().unwrap()),
+            operator: Operator::GreaterEqual,
+            right: SideRule::Literal(Value::U64(18)),
+        }
+        .into(),
+    ]]);
 
     let result = evaluate(&user, &task, &rules);
     assert!(result.unwrap(), "Case 01: age >= 18 should pass");
@@ -99,11 +103,14 @@ async fn evaluate_test() {
     /* -----------------------------------------------
      * Case 02 – Subject.name == Object.owner → true
      * ----------------------------------------------- */
-    let rules = Rules(vec![vec![Rule {
-        left: SideRule::Subject("name".into()),
-        operator: Operator::Equal,
-        right: SideRule::Object("owner".into()),
-    }]]);
+    let rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Object("owner".parse().unwrap()),
+        }
+        .into(),
+    ]]);
 
     let result = evaluate(&user, &task, &rules);
     assert!(result.unwrap(), "Case 02: name == owner should pass");
@@ -111,11 +118,14 @@ async fn evaluate_test() {
     /* -----------------------------------------------
      * Case 03 – Subject.age > 30 → false
      * ----------------------------------------------- */
-    let rules = Rules(vec![vec![Rule {
-        left: SideRule::Subject("age".into()),
-        operator: Operator::Greater,
-        right: SideRule::Literal(Value::U64(30)),
-    }]]);
+    let rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("age".parse().unwrap()),
+            operator: Operator::Greater,
+            right: SideRule::Literal(Value::U64(30)),
+        }
+        .into(),
+    ]]);
 
     let result = evaluate(&user, &task, &rules);
     assert!(!result.unwrap(), "Case 03: age > 30 should fail");
@@ -125,15 +135,17 @@ async fn evaluate_test() {
      * ----------------------------------------------- */
     let rules = Rules(vec![vec![
         Rule {
-            left: SideRule::Subject("age".into()),
+            left: SideRule::Subject("age".parse().unwrap()),
             operator: Operator::Greater,
             right: SideRule::Literal(Value::U64(30)),
-        },
+        }
+        .into(),
         Rule {
-            left: SideRule::Subject("name".into()),
+            left: SideRule::Subject("name".parse().unwrap()),
             operator: Operator::Equal,
-            right: SideRule::Object("owner".into()),
-        },
+            right: SideRule::Object("owner".parse().unwrap()),
+        }
+        .into(),
     ]]);
 
     let result = evaluate(&user, &task, &rules);
@@ -143,16 +155,22 @@ async fn evaluate_test() {
      * Case 05 – AND group fail: (age >= 18) AND (name == 'SomeoneElse') → false
      * ----------------------------------------------- */
     let rules = Rules(vec![
-        vec![Rule {
-            left: SideRule::Subject("age".into()),
-            operator: Operator::GreaterEqual,
-            right: SideRule::Literal(Value::U64(18)),
-        }],
-        vec![Rule {
-            left: SideRule::Subject("name".into()),
-            operator: Operator::Equal,
-            right: SideRule::Literal(Value::String("SomeoneElse".into())),
-        }],
+        vec![
+            Rule {
+                left: SideRule::Subject("age".parse().unwrap()),
+                operator: Operator::GreaterEqual,
+                right: SideRule::Literal(Value::U64(18)),
+            }
+            .into(),
+        ],
+        vec![
+            Rule {
+                left: SideRule::Subject("name".parse().unwrap()),
+                operator: Operator::Equal,
+                right: SideRule::Literal(Value::String("SomeoneElse".into())),
+            }
+            .into(),
+        ],
     ]);
 
     let result = evaluate(&user, &task, &rules);
@@ -163,17 +181,22 @@ async fn evaluate_test() {
      * ----------------------------------------------- */
     let rules = Rules(vec![vec![
         Rule {
-            left: SideRule::Subject("name".into()),
+            left: SideRule::Subject("name".parse().unwrap()),
             operator: Operator::Equal,
-            right: SideRule::Object("owner".into()),
-        },
+            right: SideRule::Object("owner".parse().unwrap()),
+        }
+        .into(),
         Rule {
-            left: SideRule::Subject("name".into()),
+            left: SideRule::Subject("name".parse().unwrap()),
             operator: Operator::Equal,
-            right: SideRule::Object("editor".into()),
-        },
+            right: SideRule::Object("editor".parse().unwrap()),
+        }
+        .into(),
     ]]);
 
     let result = evaluate(&user, &task, &rules);
-    assert!(result.unwrap(), "Case 06: name == owner, but field not found...ignored n still should pass");
+    assert!(
+        result.unwrap(),
+        "Case 06: name == owner, but field not found...ignored n still should pass"
+    );
 }
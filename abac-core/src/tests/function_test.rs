@@ -0,0 +1,499 @@
+use std::path::PathBuf;
+
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    AttributeType, Builtin, CustomFunction, Engine, Entity, EntityAdapter, Error, EvaluateEntity,
+    FunctionCall, LoadResult, Operator, Rule, Rules, SideRule, evaluate, object, subject,
+};
+
+#[derive(Entity, Default)]
+struct User {
+    name: String,
+    tags: Vec<String>,
+    created_at: String,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+fn call(name: &str, args: Vec<SideRule>) -> SideRule {
+    SideRule::Function(FunctionCall {
+        name: name.into(),
+        args,
+    })
+}
+
+fn rule(left: SideRule, operator: Operator, right: SideRule) -> Rules {
+    Rule {
+        left,
+        operator,
+        right,
+    }
+    .into()
+}
+
+fn user() -> User {
+    User {
+        name: "  WiszeL ".into(),
+        tags: vec!["admin".into(), "dev".into()],
+        created_at: "2025-01-01T00:00:00Z".into(),
+    }
+}
+
+#[test]
+fn evaluate_function_test() {
+    // ##### Arrange ##### //
+    let user = user();
+
+    let lower = rule(
+        call(
+            "lower",
+            vec![call(
+                "trim",
+                vec![SideRule::Subject("name".parse().unwrap())],
+            )],
+        ),
+        Operator::Equal,
+        SideRule::Literal(Value::String("wiszel".into())),
+    );
+    let len = rule(
+        call("len", vec![SideRule::Subject("tags".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::U64(2)),
+    );
+    let max = rule(
+        call(
+            "max",
+            vec![
+                SideRule::Literal(Value::I64(-3)),
+                SideRule::Literal(Value::F64(2.5)),
+            ],
+        ),
+        Operator::Equal,
+        SideRule::Literal(Value::F64(2.5)),
+    );
+    let date_diff = rule(
+        call(
+            "date_diff",
+            vec![
+                SideRule::Literal(Value::String("2025-01-02".into())),
+                SideRule::Subject("created_at".parse().unwrap()),
+            ],
+        ),
+        Operator::Equal,
+        // A day apart, in seconds
+        SideRule::Literal(Value::I64(86_400)),
+    );
+    let split = rule(
+        call(
+            "concat",
+            vec![
+                SideRule::Subject("tags".parse().unwrap()),
+                call(
+                    "split",
+                    vec![
+                        SideRule::Literal(Value::String("ops,qa".into())),
+                        SideRule::Literal(Value::String(",".into())),
+                    ],
+                ),
+            ],
+        ),
+        Operator::Equal,
+        SideRule::Literal(Value::Seq(
+            ["admin", "dev", "ops", "qa"]
+                .into_iter()
+                .map(|tag| Value::String(tag.into()))
+                .collect(),
+        )),
+    );
+    let now = rule(
+        call("now", Vec::new()),
+        Operator::Greater,
+        SideRule::Subject("created_at".parse().unwrap()),
+    );
+
+    // ##### Act & Assert ##### //
+    for (name, rules) in [
+        ("lower", lower),
+        ("len", len),
+        ("max", max),
+        ("date_diff", date_diff),
+        ("concat", split),
+        ("now", now),
+    ] {
+        assert!(
+            evaluate(&user, &user, &rules).unwrap(),
+            "`{name}` should evaluate to the expected value"
+        );
+    }
+}
+
+#[test]
+fn evaluate_function_error_test() {
+    // ##### Arrange ##### //
+    let user = user();
+
+    let unknown = rule(
+        call("reverse", vec![SideRule::Subject("name".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::String("LeziW".into())),
+    );
+    let invalid = rule(
+        call("abs", vec![SideRule::Subject("name".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::U64(1)),
+    );
+
+    // ##### Act ##### //
+    let unknown = evaluate(&user, &user, &unknown);
+    let invalid = evaluate(&user, &user, &invalid);
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(unknown, Err(Error::UnknownFunction(ref name)) if name == "reverse"),
+        "Unknown function should fail"
+    );
+    assert!(
+        matches!(invalid, Err(Error::InvalidArguments { ref function, .. }) if function == "abs"),
+        "Wrong argument should fail"
+    );
+}
+
+#[test]
+fn function_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"{
+        "left": { "Function": { "name": "lower", "args": [{ "Subject": "name" }] } },
+        "operator": "Equal",
+        "right": { "Function": { "name": "now" } }
+    }"#;
+
+    // ##### Act ##### //
+    let rule: Rule = serde_json::from_str(json).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        rule,
+        Rule {
+            left: call("lower", vec![SideRule::Subject("name".parse().unwrap())]),
+            operator: Operator::Equal,
+            right: call("now", Vec::new()),
+        },
+        "Function should deserialize from its name and arguments"
+    );
+}
+
+#[test]
+fn validate_function_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<User>("user");
+
+    let valid = rule(
+        call("len", vec![SideRule::Subject("tags".parse().unwrap())]),
+        Operator::Greater,
+        SideRule::Literal(Value::U64(0)),
+    );
+    let unknown = rule(
+        call("reverse", vec![SideRule::Subject("name".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::String("LeziW".into())),
+    );
+    let wrong_type = rule(
+        call("upper", vec![SideRule::Subject("tags".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::String("ADMIN".into())),
+    );
+    let wrong_arity = rule(
+        call("split", vec![SideRule::Subject("name".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::Seq(Vec::new())),
+    );
+    let unknown_path = rule(
+        call("len", vec![SideRule::Subject("nickname".parse().unwrap())]),
+        Operator::Equal,
+        SideRule::Literal(Value::U64(0)),
+    );
+    let mismatch = rule(
+        call("len", vec![SideRule::Subject("tags".parse().unwrap())]),
+        Operator::Greater,
+        SideRule::Literal(Value::String("10".into())),
+    );
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("user", "user", &valid).is_ok(),
+        "Function with the right types should be valid"
+    );
+    assert!(
+        matches!(engine.validate("user", "user", &mismatch), Err(Error::InvalidOperands { ref operator, .. }) if operator == "Greater"),
+        "Integer compared with a string should be invalid"
+    );
+    assert!(
+        matches!(engine.validate("user", "user", &unknown), Err(Error::UnknownFunction(ref name)) if name == "reverse"),
+        "Unknown function should be invalid"
+    );
+    assert!(
+        matches!(engine.validate("user", "user", &wrong_type), Err(Error::InvalidArguments { ref function, .. }) if function == "upper"),
+        "Wrong argument type should be invalid"
+    );
+    assert!(
+        matches!(engine.validate("user", "user", &wrong_arity), Err(Error::InvalidArguments { ref function, .. }) if function == "split"),
+        "Wrong argument count should be invalid"
+    );
+    assert!(
+        matches!(engine.validate("user", "user", &unknown_path), Err(Error::UnknownAttribute { ref path, .. }) if path == "nickname"),
+        "Unknown path inside a function should be invalid"
+    );
+}
+
+#[test]
+fn check_function_test() {
+    // ##### Arrange ##### //
+    let integers = [AttributeType::Integer, AttributeType::Integer];
+    let mixed = [AttributeType::Integer, AttributeType::Float];
+    let timestamps = [AttributeType::Timestamp, AttributeType::String];
+
+    // ##### Act & Assert ##### //
+    assert_eq!(
+        Builtin::Min.check(&integers),
+        Ok(AttributeType::Integer),
+        "`min` of integers should be an integer"
+    );
+    assert_eq!(
+        Builtin::Max.check(&mixed),
+        Ok(AttributeType::Float),
+        "`max` with a float should be a float"
+    );
+    assert_eq!(
+        Builtin::DateDiff.check(&timestamps),
+        Ok(AttributeType::Integer),
+        "`date_diff` should be a number of seconds"
+    );
+}
+
+/// Clearance levels ordered from the lowest
+const LEVELS: [&str; 3] = ["public", "secret", "top_secret"];
+
+/// `subject's clearance` dominates `resource's classification`
+struct Dominates;
+
+impl CustomFunction for Dominates {
+    fn name(&self) -> &'static str {
+        "dominates"
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn parameters(&self) -> Vec<AttributeType> {
+        vec![AttributeType::String, AttributeType::String]
+    }
+
+    fn return_type(&self) -> AttributeType {
+        AttributeType::Bool
+    }
+
+    fn call(&self, args: &[Value]) -> Result<Value, Error> {
+        let level = |value: &Value| match value {
+            Value::String(level) => LEVELS.iter().position(|l| l == level),
+            _ => None,
+        };
+
+        match (level(&args[0]), level(&args[1])) {
+            (Some(clearance), Some(classification)) => Ok(Value::Bool(clearance >= classification)),
+            _ => Err(Error::InvalidArguments {
+                function: self.name().into(),
+                reason: "unknown level".into(),
+            }),
+        }
+    }
+}
+
+/// Domain of an email address
+struct Domain;
+
+impl CustomFunction for Domain {
+    fn name(&self) -> &'static str {
+        "domain"
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn parameters(&self) -> Vec<AttributeType> {
+        vec![AttributeType::String]
+    }
+
+    fn return_type(&self) -> AttributeType {
+        AttributeType::String
+    }
+
+    fn call(&self, args: &[Value]) -> Result<Value, Error> {
+        match &args[0] {
+            Value::String(email) => Ok(Value::String(
+                email.rsplit('@').next().unwrap_or_default().into(),
+            )),
+            _ => Ok(Value::Unit),
+        }
+    }
+}
+
+#[derive(Entity, Default)]
+struct Agent {
+    email: String,
+    clearance: String,
+}
+
+impl EntityAdapter for Agent {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                email: "wiszel@agency.gov".into(),
+                clearance: "secret".into(),
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Document {
+    classification: String,
+}
+
+impl EntityAdapter for Document {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                classification: "public".into(),
+            })
+        })
+    }
+}
+
+fn custom_engine() -> Engine {
+    Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<Agent>("agent")
+        .register_adapter::<Document>("document")
+        .register_function(Dominates)
+        .register_function(Domain)
+}
+
+#[tokio::test]
+async fn evaluate_custom_test() {
+    // ##### Arrange ##### //
+    let engine = custom_engine();
+
+    let rules = Rules::new()
+        .and(subject(Agent::CLEARANCE).is("dominates", object(Document::CLASSIFICATION)))
+        .and(Rule {
+            left: call("domain", vec![SideRule::Subject("email".parse().unwrap())]),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("agency.gov".into())),
+        });
+    let top_secret =
+        Rules::new().and(subject(Agent::CLEARANCE).is("dominates", String::from("top_secret")));
+
+    // ##### Act ##### //
+    let evaluate = |rules| {
+        engine.evaluate(
+            EvaluateEntity::new("agent", Some(Uuid::nil())),
+            EvaluateEntity::new("document", Some(Uuid::nil())),
+            rules,
+        )
+    };
+    let result = evaluate(&rules).await;
+    let top_secret = evaluate(&top_secret).await;
+
+    // ##### Assert ##### //
+    assert!(result.unwrap(), "Custom operator and function should pass");
+    assert!(
+        !top_secret.unwrap(),
+        "Lower clearance shouldn't dominate the classification"
+    );
+}
+
+#[test]
+fn evaluate_custom_without_engine_test() {
+    // ##### Arrange ##### //
+    let user = user();
+
+    let rules = rule(
+        SideRule::Subject("name".parse().unwrap()),
+        Operator::Custom("dominates".into()),
+        SideRule::Subject("name".parse().unwrap()),
+    );
+
+    // ##### Act ##### //
+    let result = evaluate(&user, &user, &rules);
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(result, Err(Error::UnknownFunction(ref name)) if name == "dominates"),
+        "Custom operator should need the engine"
+    );
+}
+
+#[test]
+fn validate_custom_test() {
+    // ##### Arrange ##### //
+    let engine = custom_engine();
+
+    let valid = rule(
+        SideRule::Subject("clearance".parse().unwrap()),
+        Operator::Custom("dominates".into()),
+        SideRule::Object("classification".parse().unwrap()),
+    );
+    let unknown = rule(
+        SideRule::Subject("clearance".parse().unwrap()),
+        Operator::Custom("outranks".into()),
+        SideRule::Object("classification".parse().unwrap()),
+    );
+    let not_bool = rule(
+        SideRule::Subject("email".parse().unwrap()),
+        Operator::Custom("domain".into()),
+        SideRule::Object("classification".parse().unwrap()),
+    );
+    let wrong_type = rule(
+        call("domain", vec![SideRule::Literal(Value::U64(1))]),
+        Operator::Equal,
+        SideRule::Literal(Value::String("agency.gov".into())),
+    );
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("agent", "document", &valid).is_ok(),
+        "Registered operator should be valid"
+    );
+    assert!(
+        matches!(engine.validate("agent", "document", &unknown), Err(Error::UnknownFunction(ref name)) if name == "outranks"),
+        "Unregistered operator should be invalid"
+    );
+    assert!(
+        matches!(engine.validate("agent", "document", &not_bool), Err(Error::InvalidArguments { ref function, .. }) if function == "domain"),
+        "Operator should take 2 arguments and return a bool"
+    );
+    assert!(
+        matches!(engine.validate("agent", "document", &wrong_type), Err(Error::InvalidArguments { ref function, .. }) if function == "domain"),
+        "Custom function should check its parameter types"
+    );
+}
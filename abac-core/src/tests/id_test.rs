@@ -0,0 +1,199 @@
+use std::{convert::Infallible, path::PathBuf};
+
+use uuid::Uuid;
+
+use crate::{
+    Engine, Entity, EntityAdapter, EntityId, Error, EvaluateEntity, LoadResult, Operator, Rule,
+    Rules, SideRule, object, subject,
+};
+
+#[derive(Entity, Default)]
+struct User {
+    name: String,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = String;
+
+    // Assume it loads data from database, keyed by the username
+    fn load_data(id: String, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self { name: id }) })
+    }
+}
+
+async fn load_order(id: i64, _: &PathBuf) -> Result<Order, Infallible> {
+    Ok(Order {
+        number: id,
+        customer: "wiszel".into(),
+    })
+}
+
+#[derive(Entity, EntityAdapter, Default)]
+#[abac(provider = PathBuf, load = load_order, id = i64)]
+struct Order {
+    number: i64,
+    customer: String,
+}
+
+/// Line of an order, keyed by the order number and its position
+struct LineKey {
+    order: i64,
+    position: i64,
+}
+
+impl TryFrom<EntityId> for LineKey {
+    type Error = Error;
+
+    fn try_from(id: EntityId) -> Result<Self, Self::Error> {
+        match Vec::<EntityId>::try_from(id)?.as_slice() {
+            [EntityId::Int(order), EntityId::Int(position)] => Ok(Self {
+                order: *order,
+                position: *position,
+            }),
+            parts => Err(EntityId::Composite(parts.to_vec()).invalid::<Self>()),
+        }
+    }
+}
+
+#[derive(Entity, Default)]
+struct Line {
+    position: i64,
+    #[abac(ref = "order", alias = "order")]
+    order_id: i64,
+}
+
+impl EntityAdapter for Line {
+    type Provider = PathBuf;
+    type Id = LineKey;
+
+    // Assume it loads data from database
+    fn load_data(id: LineKey, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                position: id.position,
+                order_id: id.order,
+            })
+        })
+    }
+}
+
+fn engine() -> Engine {
+    Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Order>("order")
+        .register_adapter::<Line>("line")
+}
+
+#[tokio::test]
+async fn evaluate_with_ids_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let customer = Rules::from(subject(User::NAME).eq(object(Order::CUSTOMER)));
+    let line_customer = Rules::from(Rule {
+        left: SideRule::Subject("name".parse().unwrap()),
+        operator: Operator::Equal,
+        right: SideRule::Object("order.customer".parse().unwrap()),
+    });
+
+    // ##### Act ##### //
+    let slug_and_int = engine
+        .evaluate(
+            EvaluateEntity::by_id("user", "wiszel"),
+            EvaluateEntity::by_id("order", 42),
+            &customer,
+        )
+        .await;
+    let composite = engine
+        .evaluate(
+            EvaluateEntity::by_id("user", "wiszel"),
+            EvaluateEntity::by_id("line", vec![42.into(), 1.into()]),
+            &line_customer,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(slug_and_int.unwrap(), "Customer of the order should pass");
+    assert!(
+        composite.unwrap(),
+        "Customer of the line's order should pass"
+    );
+}
+
+#[tokio::test]
+async fn id_mismatch_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let rules = Rules::from(subject(User::NAME).eq(object(Order::CUSTOMER)));
+
+    // ##### Act ##### //
+    let slug_order = engine
+        .evaluate(
+            EvaluateEntity::by_id("user", "wiszel"),
+            EvaluateEntity::by_id("order", "42"),
+            &rules,
+        )
+        .await;
+    let short_line = engine
+        .evaluate(
+            EvaluateEntity::by_id("user", "wiszel"),
+            EvaluateEntity::by_id("line", vec![EntityId::from(42)]),
+            &rules,
+        )
+        .await;
+    let uuid_user = engine
+        .evaluate(
+            EvaluateEntity::by_id("user", Uuid::nil()),
+            EvaluateEntity::by_id("order", 42),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(
+            slug_order,
+            Err(Error::InvalidEntityId {
+                expected: "i64",
+                ..
+            })
+        ),
+        "String id shouldn't be taken as a number"
+    );
+    assert!(
+        matches!(short_line, Err(Error::InvalidEntityId { ref id, .. }) if id.to_string() == "42"),
+        "Composite key should have every part"
+    );
+    assert!(
+        !uuid_user.unwrap(),
+        "UUID should be taken as a string, for a username that doesn't match"
+    );
+}
+
+#[test]
+fn entity_id_test() {
+    // ##### Arrange ##### //
+    let json = r#"["7d9f1b1e-4b1a-4a7e-9a57-6f2e1c3d4b5a", 42, "my-slug", ["acme", 7]]"#;
+
+    // ##### Act ##### //
+    let ids: Vec<EntityId> = serde_json::from_str(json).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        ids,
+        vec![
+            EntityId::Uuid("7d9f1b1e-4b1a-4a7e-9a57-6f2e1c3d4b5a".parse().unwrap()),
+            EntityId::Int(42),
+            EntityId::String("my-slug".into()),
+            EntityId::Composite(vec!["acme".into(), 7.into()]),
+        ],
+        "Should take the id form from the JSON value"
+    );
+    assert_eq!(ids[3].to_string(), "acme,7", "Parts should be joined");
+    assert_eq!(
+        Uuid::try_from(EntityId::from("7d9f1b1e-4b1a-4a7e-9a57-6f2e1c3d4b5a")).ok(),
+        Some("7d9f1b1e-4b1a-4a7e-9a57-6f2e1c3d4b5a".parse().unwrap()),
+        "UUID should also be taken from its string form"
+    );
+}
@@ -1,4 +1,27 @@
+mod common;
 mod evaluator_test;
 mod rules_test;
 mod adapter_test;
 mod engine_test;
+mod entity_test;
+mod builder_test;
+mod schema_test;
+mod path_test;
+mod reference_test;
+mod relation_test;
+mod role_test;
+mod resolver_test;
+mod function_test;
+mod time_test;
+mod network_test;
+mod arithmetic_test;
+mod quantifier_test;
+mod definition_test;
+mod policy_test;
+mod template_test;
+mod request_test;
+mod preloaded_test;
+mod id_test;
+mod not_found_test;
+mod anonymous_test;
+mod provider_test;
@@ -0,0 +1,219 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::PathBuf,
+};
+
+use serde::Serialize;
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    AttributeSchema, AttributeType, Cidr, Engine, Entity, EntityAdapter, Error, LoadResult,
+    Operator, Rule, Rules, SideRule, evaluate, subject,
+};
+
+#[derive(Entity, Serialize)]
+struct Request {
+    ip: IpAddr,
+    allowed: Vec<Cidr>,
+}
+
+impl Default for Request {
+    fn default() -> Self {
+        Self {
+            ip: IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)),
+            allowed: vec!["192.168.0.0/16".parse().unwrap()],
+        }
+    }
+}
+
+impl EntityAdapter for Request {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+fn cidr(block: &str) -> Cidr {
+    block.parse().unwrap()
+}
+
+#[test]
+fn cidr_test() {
+    // ##### Arrange ##### //
+    let v4 = |a, b, c, d| IpAddr::V4(Ipv4Addr::new(a, b, c, d));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        cidr("10.0.0.0/8").contains(&v4(10, 255, 0, 1)),
+        "Should be in the block"
+    );
+    assert!(
+        !cidr("10.0.0.0/8").contains(&v4(11, 0, 0, 1)),
+        "Should be outside the block"
+    );
+    assert!(
+        cidr("0.0.0.0/0").contains(&v4(8, 8, 8, 8)),
+        "/0 should match everything"
+    );
+    assert!(
+        cidr("1.2.3.4").contains(&v4(1, 2, 3, 4)),
+        "Bare address should be a single host"
+    );
+    assert!(
+        !cidr("1.2.3.4").contains(&v4(1, 2, 3, 5)),
+        "Bare address should be a single host"
+    );
+    assert!(
+        cidr("2001:db8::/32").contains(&"2001:db8:1::1".parse().unwrap()),
+        "Should match IPv6"
+    );
+    assert!(
+        cidr("10.0.0.0/8").contains(&IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped())),
+        "IPv4-mapped address should match the IPv4 block"
+    );
+    assert!(
+        !cidr("::/0").contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        "IPv4 shouldn't match an IPv6 block"
+    );
+    assert_eq!(
+        cidr("10.0.0.0/8").to_string(),
+        "10.0.0.0/8",
+        "Should display as written"
+    );
+    for invalid in ["10.0.0.0/33", "::/129", "10.0.0/8", "10.0.0.0/", "office"] {
+        assert!(
+            matches!(invalid.parse::<Cidr>(), Err(Error::InvalidCidr(ref block)) if block == invalid),
+            "`{invalid}` should be rejected"
+        );
+    }
+}
+
+#[test]
+fn network_attributes_test() {
+    // ##### Act ##### //
+    let attributes = Request::default().attributes();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        attributes,
+        vec![
+            AttributeSchema::new("ip", AttributeType::IpAddress),
+            AttributeSchema::new(
+                "allowed",
+                AttributeType::List(Box::new(AttributeType::Cidr))
+            ),
+        ],
+        "Network fields should have network types"
+    );
+}
+
+#[test]
+fn evaluate_cidr_test() {
+    // ##### Arrange ##### //
+    let request = Request::default();
+    let v6 = Request {
+        ip: IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ..Request::default()
+    };
+
+    let office = Rules::new().and(subject(Request::IP).in_cidr([cidr("10.0.0.0/8")]));
+    let tor = Rules::new()
+        .and(subject(Request::IP).not_in_cidr([cidr("185.220.101.0/24"), cidr("10.1.2.3")]));
+    let allowed = Rules::new().and(Rule {
+        left: SideRule::Subject("ip".parse().unwrap()),
+        operator: Operator::InCidr,
+        right: SideRule::Object("allowed".parse().unwrap()),
+    });
+    let loopback = Rules::new().and(subject(Request::IP).in_cidr([cidr("::1/128")]));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        evaluate(&request, &request, &office).unwrap(),
+        "Should be in the office range"
+    );
+    assert!(
+        !evaluate(&request, &request, &tor).unwrap(),
+        "Listed exit should be denied"
+    );
+    assert!(
+        !evaluate(&request, &request, &allowed).unwrap(),
+        "Should read the blocks from the attribute"
+    );
+    assert!(evaluate(&v6, &v6, &loopback).unwrap(), "Should match IPv6");
+}
+
+#[test]
+fn cidr_serde_test() {
+    // ##### Arrange ##### //
+    let rule = |right: &str| {
+        format!(
+            r#"{{ "left": {{ "Subject": "ip" }}, "operator": "InCidr", "right": {{ "Literal": {right} }} }}"#
+        )
+    };
+
+    // ##### Act ##### //
+    let single = serde_json::from_str::<Rule>(&rule(r#""10.0.0.0/8""#));
+    let list = serde_json::from_str::<Rule>(&rule(r#"["10.0.0.0/8", "::1"]"#));
+    let invalid = serde_json::from_str::<Rule>(&rule(r#"["10.0.0.0/8", "10.0.0.0/40"]"#));
+    let other = serde_json::from_str::<Rule>(
+        r#"{ "left": { "Subject": "ip" }, "operator": "Equal", "right": { "Literal": "10.0.0.0/40" } }"#,
+    );
+
+    // ##### Assert ##### //
+    assert_eq!(
+        single.unwrap().right,
+        SideRule::Cidr(vec![cidr("10.0.0.0/8")]),
+        "Single block should be parsed once"
+    );
+    assert_eq!(
+        list.unwrap().right,
+        SideRule::Cidr(vec![cidr("10.0.0.0/8"), cidr("::1/128")]),
+        "List of blocks should be parsed once"
+    );
+    assert!(
+        invalid
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid CIDR block: 10.0.0.0/40"),
+        "Malformed block should be rejected"
+    );
+    assert_eq!(
+        other.unwrap().right,
+        SideRule::Literal(Value::String("10.0.0.0/40".into())),
+        "Other operators should keep their literal"
+    );
+}
+
+#[test]
+fn validate_cidr_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<Request>("request");
+
+    let valid = Rules::new().and(Rule {
+        left: SideRule::Subject("ip".parse().unwrap()),
+        operator: Operator::NotInCidr,
+        right: SideRule::Object("allowed".parse().unwrap()),
+    });
+    let invalid = Rules::new().and(Rule {
+        left: SideRule::Subject("allowed".parse().unwrap()),
+        operator: Operator::InCidr,
+        right: SideRule::Cidr(vec![cidr("10.0.0.0/8")]),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("request", "request", &valid).is_ok(),
+        "IP against a list of blocks should be valid"
+    );
+    assert!(
+        matches!(
+            engine.validate("request", "request", &invalid),
+            Err(Error::InvalidOperands { .. })
+        ),
+        "List of blocks isn't an IP address"
+    );
+}
@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use serde_value::Value;
+use uuid::Uuid;
+
+use super::common::{User, user};
+use crate::{
+    Decision, Engine, Entity, EntityAdapter, Error, EvaluateEntity, LoadResult, MissingResource,
+    Operator, Rule, Rules, SideRule, object, subject,
+};
+
+const MISSING: Uuid = Uuid::from_u128(1);
+const BROKEN: Uuid = Uuid::from_u128(2);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Connection refused")]
+struct Outage;
+
+#[derive(Entity, Default)]
+struct Project {
+    name: String,
+}
+
+impl EntityAdapter for Project {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database, the project was deleted
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Err(Error::NotFound) })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Task {
+    owner: String,
+    #[abac(ref = "project", alias = "project")]
+    project_id: Uuid,
+}
+
+impl EntityAdapter for Task {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database, some rows don't exist and the database may be down
+    fn load_data(id: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            match id {
+                MISSING => Err(Error::NotFound),
+                BROKEN => Err(Error::load_error(Outage)),
+                _ => Ok(Self {
+                    owner: "WiszeL".into(),
+                    project_id: MISSING,
+                }),
+            }
+        })
+    }
+}
+
+fn engine(missing_resource: MissingResource) -> Engine {
+    Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Task>("task")
+        .register_adapter::<Project>("project")
+        .with_missing_resource(missing_resource)
+}
+
+async fn decide(engine: &Engine, task: Uuid) -> Result<Decision, Error> {
+    engine
+        .decide(
+            user(),
+            EvaluateEntity::new("task", Some(task)),
+            &subject(User::NAME).eq(object(Task::OWNER)).into(),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn missing_resource_test() {
+    // ##### Arrange ##### //
+    let error = engine(MissingResource::default());
+    let deny = engine(MissingResource::Deny);
+    let not_applicable = engine(MissingResource::NotApplicable);
+
+    // ##### Act ##### //
+    let found = decide(&error, Uuid::nil()).await;
+    let error_result = decide(&error, MISSING).await;
+    let deny_result = decide(&deny, MISSING).await;
+    let not_applicable_result = decide(&not_applicable, MISSING).await;
+    let evaluate_result = not_applicable
+        .evaluate(
+            user(),
+            EvaluateEntity::new("task", Some(MISSING)),
+            &Rules::new(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert_eq!(
+        found.unwrap(),
+        Decision::Permit,
+        "Owner should be permitted"
+    );
+    assert!(
+        matches!(error_result, Err(Error::EntityNotFound { ref name, .. }) if name == "task"),
+        "Missing resource should fail by default"
+    );
+    assert_eq!(deny_result.unwrap(), Decision::Deny);
+    assert_eq!(not_applicable_result.unwrap(), Decision::NotApplicable);
+    assert!(
+        !evaluate_result.unwrap(),
+        "Missing resource shouldn't be permitted"
+    );
+}
+
+#[tokio::test]
+async fn load_failure_test() {
+    // ##### Arrange ##### //
+    let engine = engine(MissingResource::Deny);
+
+    // ##### Act ##### //
+    let result = decide(&engine, BROKEN).await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(result, Err(Error::LoadError(ref err)) if err.to_string() == "Connection refused"),
+        "Load failure should still fail"
+    );
+    assert!(
+        matches!(Error::load_error(Error::NotFound), Error::NotFound),
+        "Errors of the crate shouldn't be wrapped"
+    );
+}
+
+#[tokio::test]
+async fn missing_reference_test() {
+    // ##### Arrange ##### //
+    let engine = engine(MissingResource::NotApplicable);
+    let rules = Rules::from(Rule {
+        left: SideRule::Object("project.name".parse().unwrap()),
+        operator: Operator::Equal,
+        right: SideRule::Literal(Value::String("Website".into())),
+    });
+
+    // ##### Act ##### //
+    let result = engine
+        .decide(
+            user(),
+            EvaluateEntity::new("task", Some(Uuid::nil())),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(
+            result,
+            Err(Error::EntityNotFound { ref name, ref id }) if name == "project" && *id == MISSING.into()
+        ),
+        "Missing reference of an existing resource should fail with its registered name"
+    );
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_value::Value;
+
+use crate::{AttrPath, Entity, Error, PathSegment, Rule};
+
+#[derive(Serialize)]
+struct Address {
+    country: String,
+}
+
+#[derive(Serialize)]
+struct Member {
+    id: u64,
+}
+
+#[derive(Entity)]
+struct Project {
+    address: Address,
+    labels: HashMap<String, String>,
+    members: Vec<Member>,
+    parent: Option<Address>,
+}
+
+#[test]
+fn parse_path_test() {
+    // ##### Act & Assert ##### //
+
+    /* -----------------------------------------------
+     * Case 01 – Dotted, indexed and quoted segments
+     * ----------------------------------------------- */
+    let path = AttrPath::parse(r#"members[0].labels["env"].name"#).unwrap();
+    assert_eq!(
+        path.segments(),
+        &[
+            PathSegment::Key("members".into()),
+            PathSegment::Index(0),
+            PathSegment::Key("labels".into()),
+            PathSegment::Key("env".into()),
+            PathSegment::Key("name".into()),
+        ],
+        "Case 01: should parse every segment"
+    );
+
+    /* -----------------------------------------------
+     * Case 02 – Malformed paths are rejected
+     * ----------------------------------------------- */
+    for raw in [
+        "",
+        "a.",
+        ".a",
+        "a..b",
+        "a[0",
+        "a.[0]",
+        "a[]",
+        "a[0]b",
+        r#"a["env']"#,
+        r#"a['env"]"#,
+        r#"a["env]"#,
+        r#"a[env"]"#,
+    ] {
+        assert!(
+            AttrPath::parse(raw).is_err(),
+            "Case 02: `{raw}` should be rejected"
+        );
+    }
+
+    /* -----------------------------------------------
+     * Case 03 – Rule deserialization rejects malformed path
+     * ----------------------------------------------- */
+    let json_rule = r#"
+        {
+            "left":  { "Subject": "address..country" },
+            "operator":   "Equal",
+            "right": { "Literal": "ID" }
+        }
+        "#;
+    let rule = serde_json::from_str::<Rule>(json_rule);
+    assert!(rule.is_err(), "Case 03: should reject malformed path");
+}
+
+#[test]
+fn invalid_path_try_from_test() {
+    // ##### Act ##### //
+    let invalid = AttrPath::try_from("a..b");
+    let valid = AttrPath::try_from("address.country".to_string());
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(invalid, Err(Error::InvalidPath(ref raw)) if raw == "a..b"),
+        "Invalid path should be an error instead of a panic"
+    );
+    assert_eq!(
+        valid.unwrap().as_str(),
+        "address.country",
+        "Should keep the raw path"
+    );
+}
+
+#[test]
+fn resolve_path_test() {
+    // ##### Arrange ##### //
+    let project = Project {
+        address: Address {
+            country: "ID".into(),
+        },
+        labels: HashMap::from([("env".to_string(), "prod".to_string())]),
+        members: vec![Member { id: 1 }, Member { id: 2 }],
+        parent: Some(Address {
+            country: "SG".into(),
+        }),
+    };
+    let resolve = |raw: &str| {
+        AttrPath::parse(raw)
+            .unwrap()
+            .resolve(&project)
+            .unwrap()
+            .map(|value| value.into_owned())
+    };
+
+    // ##### Act & Assert ##### //
+    assert_eq!(
+        resolve("address.country"),
+        Some(Value::String("ID".into())),
+        "Should resolve struct field"
+    );
+    assert_eq!(
+        resolve(r#"labels["env"]"#),
+        Some(Value::String("prod".into())),
+        "Should resolve map key"
+    );
+    assert_eq!(
+        resolve("members[1].id"),
+        Some(Value::U64(2)),
+        "Should resolve sequence index"
+    );
+    assert_eq!(
+        resolve("parent.country"),
+        Some(Value::String("SG".into())),
+        "Should go through Option"
+    );
+    assert_eq!(
+        resolve("members[5].id"),
+        None,
+        "Out of bound should be None"
+    );
+    assert_eq!(
+        resolve("address.city"),
+        None,
+        "Missing field should be None"
+    );
+}
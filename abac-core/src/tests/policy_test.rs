@@ -0,0 +1,183 @@
+use super::common::{self, Task, User, task, user};
+use crate::{
+    Condition, Engine, Error, Policies, Policy, Rules, evaluate, named, object, policy, subject,
+};
+
+fn engine() -> Engine {
+    common::engine()
+        .with_policy("task.view", subject(User::LEVEL).ge(object(Task::LEVEL)))
+        .with_policy(
+            "task.edit",
+            Rules::new()
+                .and(policy("task.view"))
+                .and(subject(User::NAME).eq(object(Task::OWNER))),
+        )
+}
+
+async fn evaluate_policy(engine: &Engine, id: &str) -> Result<bool, Error> {
+    engine.evaluate_policy(user(), task(), id).await
+}
+
+#[tokio::test]
+async fn evaluate_policy_test() {
+    // ##### Arrange ##### //
+    let engine = engine()
+        .define("can_view", policy("task.view"))
+        .with_policy("task.comment", named("can_view"));
+
+    // ##### Act ##### //
+    let view = evaluate_policy(&engine, "task.view").await;
+    let edit = evaluate_policy(&engine, "task.edit").await;
+    let comment = evaluate_policy(&engine, "task.comment").await;
+
+    // ##### Assert ##### //
+    assert!(view.unwrap(), "Higher level should view");
+    assert!(!edit.unwrap(), "Only the owner should edit");
+    assert!(
+        comment.unwrap(),
+        "Policy referenced through a named condition should pass"
+    );
+}
+
+#[tokio::test]
+async fn policy_error_test() {
+    // ##### Arrange ##### //
+    let cycle_engine = engine()
+        .with_policy(
+            "task.a",
+            Rules::new().and(policy("task.view")).and(policy("task.b")),
+        )
+        .with_policy("task.b", policy("task.a"));
+    let dangling_engine = engine().with_policy("task.delete", policy("task.admin"));
+
+    // ##### Act ##### //
+    let cycle = evaluate_policy(&cycle_engine, "task.a").await;
+    let dangling = evaluate_policy(&dangling_engine, "task.delete").await;
+    let unknown = evaluate_policy(&engine(), "task.archive").await;
+    let without_engine = evaluate(
+        &User::default(),
+        &Task::default(),
+        &policy("task.view").into(),
+    );
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(cycle, Err(Error::PolicyCycle(ref cycle)) if cycle == "task.a -> task.b -> task.a"),
+        "Cycle should be reported along with its path"
+    );
+    assert!(
+        matches!(dangling, Err(Error::UnknownPolicy(ref id)) if id == "task.admin"),
+        "Dangling reference should fail"
+    );
+    assert!(
+        matches!(unknown, Err(Error::UnknownPolicy(ref id)) if id == "task.archive"),
+        "Unknown policy should fail"
+    );
+    assert!(
+        matches!(without_engine, Err(Error::UnknownPolicy(_))),
+        "Policy reference should need the engine"
+    );
+}
+
+#[test]
+fn check_policies_test() {
+    // ##### Arrange ##### //
+    let cycle = engine().with_policy("task.view", policy("task.edit"));
+    let dangling = engine().with_policy("task.share", policy("task.admin"));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine().check_policies().is_ok(),
+        "Policies should be valid"
+    );
+    assert!(
+        matches!(
+            cycle.check_policies(),
+            Err(Error::PolicyCycle(ref cycle))
+                if cycle == "task.view -> task.edit -> task.view"
+                    || cycle == "task.edit -> task.view -> task.edit"
+        ),
+        "Cycle should be caught before evaluating"
+    );
+    assert!(
+        matches!(dangling.check_policies(), Err(Error::UnknownPolicy(ref id)) if id == "task.admin"),
+        "Dangling reference should be caught before evaluating"
+    );
+}
+
+#[test]
+fn inline_policy_test() {
+    // ##### Act ##### //
+    let inlined = engine().inline(&policy("task.edit").into()).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        inlined,
+        Rules::from(Condition::Included(Policy {
+            id: "task.edit".into(),
+            rules: Rules::new()
+                .and(Condition::Included(Policy {
+                    id: "task.view".into(),
+                    rules: subject(User::LEVEL).ge(object(Task::LEVEL)).into(),
+                }))
+                .and(subject(User::NAME).eq(object(Task::OWNER))),
+        })),
+        "References should be replaced by the policies, keeping their ids"
+    );
+}
+
+#[tokio::test]
+async fn policies_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"{
+        "task.view": [[{ "left": { "Subject": "level" }, "operator": "GreaterEqual", "right": { "Object": "level" } }]],
+        "task.edit": [[{ "Ref": "task.view" }], [{ "left": { "Subject": "name" }, "operator": "Equal", "right": { "Object": "owner" } }]]
+    }"#;
+
+    // ##### Act ##### //
+    let policies: Policies = serde_json::from_str(json).unwrap();
+    let engine = common::engine().with_policies(policies);
+    let edit = evaluate_policy(&engine, "task.edit").await;
+
+    // ##### Assert ##### //
+    assert_eq!(
+        engine.policy("task.view").map(|rules| rules.0[0].len()),
+        Some(1),
+        "Should store the policy by its id"
+    );
+    assert!(!edit.unwrap(), "Only the owner should edit");
+}
+
+#[tokio::test]
+async fn shared_policy_test() {
+    // ##### Arrange ##### //
+    // Each level references the previous one twice, inlining it would take 2^32 copies
+    let shared = (1..=32).fold(engine(), |engine, level| {
+        let previous = match level {
+            1 => "task.view".to_string(),
+            level => format!("task.level{}", level - 1),
+        };
+
+        engine.with_policy(
+            format!("task.level{level}"),
+            Rules::new().and_any([policy(&previous), policy(&previous)]),
+        )
+    });
+
+    // ##### Act ##### //
+    let compiled = shared.compile();
+    let cycle = engine()
+        .with_policy("task.view", policy("task.edit"))
+        .compile();
+
+    // ##### Assert ##### //
+    let engine = compiled.expect("Shared references should compile");
+    assert!(
+        evaluate_policy(&engine, "task.level32").await.unwrap(),
+        "Shared references should evaluate the policy they lead to"
+    );
+    assert!(
+        matches!(cycle, Err(Error::PolicyCycle(ref cycle)) if cycle == "task.edit -> task.view -> task.edit"),
+        "Cycle should be reported when compiling"
+    );
+}
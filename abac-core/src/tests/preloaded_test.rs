@@ -0,0 +1,172 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    Engine, Entity, EntityAdapter, EvaluateEntity, EvaluateRequest, LoadResult, Operator,
+    Reference, Rule, Rules, SideRule, entity, object, subject,
+};
+
+const USER_ID: Uuid = Uuid::from_u128(1);
+const TASK_ID: Uuid = Uuid::from_u128(2);
+
+/// Assume it's a database, counting how many rows are loaded
+#[derive(Default)]
+struct Db {
+    loads: AtomicUsize,
+}
+
+#[derive(Entity, Default)]
+struct User {
+    name: String,
+    #[abac(ref = "task", alias = "pinned")]
+    pinned_id: Uuid,
+}
+
+impl EntityAdapter for User {
+    type Provider = Arc<Db>;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, db: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            db.loads.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Self {
+                name: "Loaded".into(),
+                pinned_id: TASK_ID,
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Task {
+    owner: String,
+}
+
+impl EntityAdapter for Task {
+    type Provider = Arc<Db>;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, db: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            db.loads.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Self {
+                owner: "WiszeL".into(),
+            })
+        })
+    }
+}
+
+fn engine(db: Arc<Db>) -> Engine {
+    Engine::new()
+        .with_provider(db)
+        .register_adapter::<User>("user")
+        .register_adapter::<Task>("task")
+}
+
+fn session_user() -> User {
+    User {
+        name: "WiszeL".into(),
+        pinned_id: TASK_ID,
+    }
+}
+
+#[tokio::test]
+async fn borrowed_test() {
+    // ##### Arrange ##### //
+    let db = Arc::new(Db::default());
+    let engine = engine(db.clone());
+    let user = session_user();
+    let rules = Rules::from(subject(User::NAME).eq(object(Task::OWNER)));
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::preloaded("user", Some(USER_ID), &user),
+            EvaluateEntity::new("task", Some(TASK_ID)),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(result.unwrap(), "Preloaded user owns the task");
+    assert_eq!(
+        db.loads.load(Ordering::SeqCst),
+        1,
+        "Only the task should be loaded"
+    );
+}
+
+#[tokio::test]
+async fn shared_test() {
+    // ##### Arrange ##### //
+    let db = Arc::new(Db::default());
+    let engine = engine(db.clone());
+    let user: Arc<dyn Entity> = Arc::new(session_user());
+    let rules = Rules::new()
+        .and(subject(User::NAME).eq(entity("owner", User::NAME)))
+        .and(Rule {
+            left: SideRule::Subject("pinned.owner".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Entity("owner".into(), "name".parse().unwrap()),
+        });
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate_request(
+            EvaluateRequest::new(
+                EvaluateEntity::shared("user", Some(USER_ID), user.clone()),
+                EvaluateEntity::new("task", None),
+            )
+            .with_entity("owner", EvaluateEntity::shared("user", Some(USER_ID), user)),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(result.unwrap(), "Same shared user on both sides");
+    assert_eq!(
+        db.loads.load(Ordering::SeqCst),
+        1,
+        "Only the pinned task should be loaded through the reference"
+    );
+}
+
+#[tokio::test]
+async fn load_preloaded_test() {
+    // ##### Arrange ##### //
+    let db = Arc::new(Db::default());
+    let engine = engine(db.clone());
+    let user = session_user();
+
+    // ##### Act ##### //
+    let loaded = engine
+        .load(EvaluateEntity::preloaded("user", None, &user))
+        .await
+        .unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        loaded
+            .get_attribute("name")
+            .unwrap()
+            .map(|name| name.into_owned()),
+        Some(serde_value::Value::String("WiszeL".into())),
+        "Should be the preloaded user"
+    );
+    assert_eq!(
+        loaded.references(),
+        &[Reference::new("pinned_id", "task", "pinned")],
+        "Should keep the references of the preloaded user"
+    );
+    assert_eq!(
+        db.loads.load(Ordering::SeqCst),
+        0,
+        "Nothing should be loaded"
+    );
+}
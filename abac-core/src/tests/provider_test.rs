@@ -0,0 +1,153 @@
+use std::convert::Infallible;
+
+use uuid::Uuid;
+
+use crate::{
+    Engine, Entity, EntityAdapter, Error, EvaluateEntity, LoadResult, Rules, object, subject,
+};
+
+/// Assume it's a database pool, the primary one or the analytics replica
+struct Pool {
+    region: &'static str,
+}
+
+#[derive(Entity, Default)]
+struct User {
+    region: String,
+}
+
+impl EntityAdapter for User {
+    type Provider = Pool;
+    type Id = Uuid;
+
+    // Assume it loads data from the primary database
+    fn load_data(_: Uuid, pool: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                region: pool.region.into(),
+            })
+        })
+    }
+}
+
+async fn load_report(_: Uuid, pool: &Pool) -> Result<Report, Infallible> {
+    Ok(Report {
+        region: pool.region.into(),
+    })
+}
+
+#[derive(Entity, EntityAdapter, Default)]
+#[abac(provider = Pool, provider_name = "analytics", load = load_report)]
+struct Report {
+    region: String,
+}
+
+async fn load_archive(_: Uuid, pool: &Pool) -> Result<Archive, Infallible> {
+    Ok(Archive {
+        region: pool.region.into(),
+    })
+}
+
+#[derive(Entity, EntityAdapter, Default)]
+#[abac(provider = Pool, provider_name = "archive", load = load_archive)]
+struct Archive {
+    region: String,
+}
+
+fn rules() -> Rules {
+    Rules::from(subject(User::REGION).eq("primary".to_string()))
+        .and(object(Report::REGION).eq("analytics".to_string()))
+}
+
+#[tokio::test]
+async fn named_provider_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(Pool { region: "primary" })
+        .with_named_provider(
+            "analytics",
+            Pool {
+                region: "analytics",
+            },
+        )
+        .register_adapter::<User>("user")
+        .register_adapter::<Report>("report");
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::nil())),
+            EvaluateEntity::new("report", Some(Uuid::nil())),
+            &rules(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert_eq!(engine.providers.len(), 2, "Both pools should be kept");
+    assert!(
+        result.unwrap(),
+        "Each adapter should load through its own pool"
+    );
+}
+
+#[tokio::test]
+async fn missing_named_provider_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(Pool { region: "primary" })
+        .register_adapter::<User>("user")
+        .register_adapter::<Report>("report");
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::nil())),
+            EvaluateEntity::new("report", Some(Uuid::nil())),
+            &rules(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(result, Err(Error::NamedProviderNotFound(ref name)) if name == "analytics"),
+        "Named provider shouldn't fall back to the one registered by type"
+    );
+}
+
+#[tokio::test]
+async fn same_type_providers_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_named_provider(
+            "analytics",
+            Pool {
+                region: "analytics",
+            },
+        )
+        .with_named_provider("archive", Pool { region: "archive" })
+        .with_named_provider("analytics", Pool { region: "replica" })
+        .register_adapter::<Report>("report")
+        .register_adapter::<Archive>("archive");
+    let rules = Rules::from(subject(Report::REGION).eq("replica".to_string()))
+        .and(object(Archive::REGION).eq("archive".to_string()));
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("report", Some(Uuid::nil())),
+            EvaluateEntity::new("archive", Some(Uuid::nil())),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert_eq!(
+        engine.providers.len(),
+        2,
+        "Pools of the same type should be kept by name"
+    );
+    assert!(
+        result.unwrap(),
+        "Registering a name again should replace its pool"
+    );
+}
@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    Engine, Entity, EntityAdapter, Error, EvaluateEntity, LoadResult, Operator, Rule, Rules,
+    SideRule, all, any, each, element, evaluate, object, subject,
+};
+
+#[derive(Entity, Default, Clone, Serialize)]
+struct Member {
+    id: u64,
+    role: String,
+    tags: Vec<String>,
+}
+
+#[derive(Entity, Default)]
+struct Project {
+    members: Vec<Member>,
+    labels: Vec<String>,
+}
+
+impl EntityAdapter for Project {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(project()) })
+    }
+}
+
+#[derive(Entity, Default)]
+struct User {
+    id: u64,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self { id: 1 }) })
+    }
+}
+
+fn member(id: u64, role: &str, tags: &[&str]) -> Member {
+    Member {
+        id,
+        role: role.into(),
+        tags: tags.iter().map(ToString::to_string).collect(),
+    }
+}
+
+fn project() -> Project {
+    Project {
+        members: vec![
+            member(1, "maintainer", &["core"]),
+            member(2, "reporter", &["docs", "core"]),
+        ],
+        labels: vec!["public".into(), "rust".into()],
+    }
+}
+
+/// Any member with the subject's id is a maintainer
+fn maintainer() -> Rules {
+    Rules::new().and(any(
+        object(Project::MEMBERS),
+        Rules::new()
+            .and(element(Member::ID).eq(subject(User::ID)))
+            .and(element(Member::ROLE).eq("maintainer".to_string())),
+    ))
+}
+
+#[test]
+fn any_test() {
+    // ##### Arrange ##### //
+    let project = project();
+
+    // ##### Act ##### //
+    let maintainer_result = evaluate(&User { id: 1 }, &project, &maintainer());
+    let reporter_result = evaluate(&User { id: 2 }, &project, &maintainer());
+    let empty_result = evaluate(&User { id: 1 }, &Project::default(), &maintainer());
+
+    // ##### Assert ##### //
+    assert!(maintainer_result.unwrap(), "Maintainer should pass");
+    assert!(!reporter_result.unwrap(), "Reporter shouldn't pass");
+    assert!(!empty_result.unwrap(), "Any of nothing shouldn't pass");
+}
+
+#[test]
+fn all_test() {
+    // ##### Arrange ##### //
+    let project = project();
+    let user = User { id: 1 };
+
+    let core = Rules::new().and(all(
+        object(Project::MEMBERS),
+        Rules::new().and(any(
+            element(Member::TAGS),
+            each::<String>().eq("core".to_string()),
+        )),
+    ));
+    let public = Rules::new().and(all(
+        object(Project::LABELS),
+        each::<String>().eq("public".to_string()),
+    ));
+
+    // ##### Act ##### //
+    let core_result = evaluate(&user, &project, &core);
+    let public_result = evaluate(&user, &project, &public);
+    let empty_result = evaluate(&user, &Project::default(), &public);
+
+    // ##### Assert ##### //
+    assert!(
+        core_result.unwrap(),
+        "Every member should have the core tag"
+    );
+    assert!(!public_result.unwrap(), "Not every label is public");
+    assert!(empty_result.unwrap(), "All of nothing should pass");
+}
+
+#[test]
+fn quantifier_error_test() {
+    // ##### Arrange ##### //
+    let project = project();
+    let user = User { id: 1 };
+
+    let unbound = Rules::new().and(element(Member::ID).eq(1u64));
+    let not_list = Rules::new().and(any(subject(User::ID), each::<u64>().eq(1u64)));
+
+    // ##### Act ##### //
+    let unbound_result = evaluate(&user, &project, &unbound);
+    let not_list_result = evaluate(&user, &project, &not_list);
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(unbound_result, Err(Error::UnboundElement)),
+        "Element outside a quantifier should fail"
+    );
+    assert!(
+        matches!(not_list_result, Err(Error::InvalidOperands { ref operator, .. }) if operator == "Any"),
+        "Quantifier over a number should fail"
+    );
+}
+
+#[test]
+fn quantifier_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"[[{
+        "Any": {
+            "collection": { "Object": "members" },
+            "rules": [
+                [{ "left": { "Element": "id" }, "operator": "Equal", "right": { "Subject": "id" } }],
+                [{ "left": { "Element": "role" }, "operator": "Equal", "right": { "Literal": "maintainer" } }]
+            ]
+        }
+    }]]"#;
+
+    // ##### Act ##### //
+    let rules: Rules = serde_json::from_str(json).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        serde_json::to_value(&rules).unwrap(),
+        serde_json::to_value(maintainer()).unwrap(),
+        "Should deserialize into the same rules as the builder"
+    );
+    assert!(
+        evaluate(&User { id: 1 }, &project(), &rules).unwrap(),
+        "Deserialized quantifier should evaluate"
+    );
+}
+
+#[tokio::test]
+async fn engine_quantifier_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Project>("project");
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::nil())),
+            EvaluateEntity::new("project", Some(Uuid::nil())),
+            &maintainer(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        result.unwrap(),
+        "Should load the collection and the bound attributes"
+    );
+}
+
+#[test]
+fn validate_quantifier_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .register_adapter::<User>("user")
+        .register_adapter::<Project>("project");
+
+    let unknown_field = Rules::new().and(any(
+        object(Project::MEMBERS),
+        Rule {
+            left: SideRule::Element(Some("name".parse().unwrap())),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("WiszeL".into())),
+        },
+    ));
+    let unbound = Rules::new().and(element(Member::ID).eq(1u64));
+    let not_list = Rules::new().and(all(subject(User::ID), each::<u64>().eq(1u64)));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("user", "project", &maintainer()).is_ok(),
+        "Element fields should be checked against the element type"
+    );
+    assert!(
+        matches!(
+            engine.validate("user", "project", &unknown_field),
+            Err(Error::UnknownAttribute { ref path, .. }) if path == "name"
+        ),
+        "Unknown element field should be invalid"
+    );
+    assert!(
+        matches!(
+            engine.validate("user", "project", &unbound),
+            Err(Error::UnboundElement)
+        ),
+        "Element outside a quantifier should be invalid"
+    );
+    assert!(
+        matches!(
+            engine.validate("user", "project", &not_list),
+            Err(Error::InvalidOperands { ref operator, .. }) if operator == "All"
+        ),
+        "Quantifier over a number should be invalid"
+    );
+}
@@ -0,0 +1,243 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use uuid::Uuid;
+
+use crate::{
+    Engine, Entity, EntityAdapter, Error, EvaluateEntity, LoadResult, Operator, Reference, Rule,
+    Rules, SideRule,
+};
+
+const USER_ID: Uuid = Uuid::from_u128(1);
+const PROJECT_ID: Uuid = Uuid::from_u128(2);
+
+/// Assume it's a database, counting how many rows are loaded
+#[derive(Default)]
+struct Db {
+    loads: AtomicUsize,
+}
+
+#[derive(Entity, Default)]
+struct User {
+    name: String,
+}
+
+impl EntityAdapter for User {
+    type Provider = Db;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, db: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            db.loads.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Self {
+                name: "WiszeL".into(),
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Project {
+    name: String,
+    #[abac(ref = "user", alias = "owner")]
+    owner_id: Uuid,
+}
+
+impl EntityAdapter for Project {
+    type Provider = Db;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, db: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            db.loads.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Self {
+                name: "abac-rs".into(),
+                owner_id: USER_ID,
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Task {
+    #[abac(ref = "project")]
+    project_id: Option<Uuid>,
+}
+
+impl EntityAdapter for Task {
+    type Provider = Db;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, db: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            db.loads.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Self {
+                project_id: Some(PROJECT_ID),
+            })
+        })
+    }
+}
+
+fn engine() -> Engine {
+    Engine::new()
+        .with_provider(Db::default())
+        .register_adapter::<User>("user")
+        .register_adapter::<Project>("project")
+        .register_adapter::<Task>("task")
+}
+
+fn rules() -> Rules {
+    Rules(vec![
+        vec![
+            Rule {
+                left: SideRule::Object("project.owner.name".parse().unwrap()),
+                operator: Operator::Equal,
+                right: SideRule::Subject("name".parse().unwrap()),
+            }
+            .into(),
+        ],
+        vec![
+            Rule {
+                left: SideRule::Object("project.name".parse().unwrap()),
+                operator: Operator::Equal,
+                right: SideRule::Literal(serde_value::Value::String("abac-rs".into())),
+            }
+            .into(),
+        ],
+    ])
+}
+
+#[test]
+fn derive_references_test() {
+    // ##### Act & Assert ##### //
+    assert_eq!(
+        Project::default().references(),
+        &[Reference::new("owner_id", "user", "owner")],
+        "Should use the alias"
+    );
+    assert_eq!(
+        Task::default().references(),
+        &[Reference::new("project_id", "project", "project")],
+        "Alias should default to the entity name"
+    );
+}
+
+#[tokio::test]
+async fn traverse_reference_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(USER_ID)),
+            EvaluateEntity::new("task", Some(Uuid::nil())),
+            &rules(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        result.unwrap(),
+        "task.project.owner.name should be the subject's name"
+    );
+
+    let db = engine.providers.values().next().unwrap();
+    assert_eq!(
+        db.downcast_ref::<Db>()
+            .unwrap()
+            .loads
+            .load(Ordering::SeqCst),
+        4,
+        "Subject, task, project and owner should be loaded once each"
+    );
+}
+
+#[tokio::test]
+async fn max_depth_test() {
+    // ##### Arrange ##### //
+    let engine = engine().with_max_depth(1);
+
+    // ##### Act ##### //
+    let result = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(USER_ID)),
+            EvaluateEntity::new("task", Some(Uuid::nil())),
+            &rules(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(result, Err(Error::MaxDepthExceeded(1))),
+        "Should refuse to traverse deeper than max depth"
+    );
+}
+
+#[test]
+fn validate_reference_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let invalid_rules = Rules(vec![vec![
+        Rule {
+            left: SideRule::Object("project.owner.email".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Subject("name".parse().unwrap()),
+        }
+        .into(),
+    ]]);
+
+    // ##### Act ##### //
+    let valid = engine.validate("user", "task", &rules());
+    let invalid = engine.validate("user", "task", &invalid_rules);
+
+    // ##### Assert ##### //
+    assert!(valid.is_ok(), "Referenced attributes should be valid");
+    assert!(
+        matches!(invalid, Err(Error::UnknownAttribute { ref entity, .. }) if entity == "user"),
+        "Missing attribute on the referenced entity should be invalid"
+    );
+}
+
+/// Draft whose reference was stored with the wrong type
+#[derive(Entity)]
+struct Draft {
+    #[abac(ref = "project")]
+    project_id: f64,
+}
+
+#[tokio::test]
+async fn invalid_reference_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let orphan = Task { project_id: None };
+    let draft = Draft { project_id: 1.5 };
+
+    // ##### Act ##### //
+    let null = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(USER_ID)),
+            EvaluateEntity::preloaded("task", None, &orphan),
+            &rules(),
+        )
+        .await;
+    let invalid = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(USER_ID)),
+            EvaluateEntity::preloaded("draft", None, &draft),
+            &rules(),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        !null.unwrap(),
+        "Null reference should have nothing to traverse"
+    );
+    assert!(
+        matches!(invalid, Err(Error::InvalidEntityId { .. })),
+        "Reference that isn't an id shouldn't be taken for a null one"
+    );
+}
@@ -0,0 +1,286 @@
+use std::{path::PathBuf, sync::Arc};
+
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    Condition, Engine, Entity, EntityAdapter, Error, EvaluateEntity, InMemoryTupleStore,
+    LoadResult, MAX_RELATION_DEPTH, ObjectRef, Operator, RelationTuple, Relations, Rewrite, Rule,
+    Rules, SideRule, Target, TupleSubject, evaluate, has_relation, policy,
+};
+
+const ALICE: Uuid = Uuid::from_u128(1);
+const README: Uuid = Uuid::from_u128(2);
+
+fn tuple(raw: &str) -> RelationTuple {
+    raw.parse().unwrap()
+}
+
+/// doc viewers are its editors, its direct viewers and the viewers of its parent folder
+fn relations(store: Arc<InMemoryTupleStore>) -> Relations {
+    Relations::new(store).with_rewrite(
+        "doc",
+        "viewer",
+        Rewrite::Union(vec![
+            Rewrite::This,
+            Rewrite::Computed("editor".into()),
+            Rewrite::TupleToUserset {
+                tupleset: "parent".into(),
+                computed: "viewer".into(),
+            },
+        ]),
+    )
+}
+
+#[test]
+fn parse_tuple_test() {
+    // ##### Act ##### //
+    let direct = tuple("doc:readme#viewer@user:alice");
+    let userset = tuple("folder:root#viewer@group:eng#member");
+
+    // ##### Assert ##### //
+    assert_eq!(direct.object, ObjectRef::new("doc", "readme"));
+    assert_eq!(direct.relation, "viewer");
+    assert_eq!(
+        direct.subject,
+        TupleSubject::Direct(ObjectRef::new("user", "alice"))
+    );
+    assert_eq!(
+        userset.subject,
+        TupleSubject::Userset(ObjectRef::new("group", "eng"), "member".into())
+    );
+    assert_eq!(
+        userset.to_string(),
+        "folder:root#viewer@group:eng#member",
+        "Should format back into the same tuple"
+    );
+
+    for raw in [
+        "doc:readme#viewer",
+        "doc#viewer@user:alice",
+        "doc:readme#@user:alice",
+    ] {
+        assert!(
+            matches!(raw.parse::<RelationTuple>(), Err(Error::InvalidTuple(_))),
+            "`{raw}` should be rejected"
+        );
+    }
+}
+
+#[tokio::test]
+async fn check_test() {
+    // ##### Arrange ##### //
+    let store = Arc::new(InMemoryTupleStore::new());
+    store.write(tuple("group:eng#member@user:alice"));
+    store.write(tuple("folder:root#viewer@group:eng#member"));
+    store.write(tuple("doc:readme#parent@folder:root"));
+    store.write(tuple("doc:guide#editor@user:bob"));
+
+    let relations = relations(store.clone());
+    let alice = ObjectRef::new("user", "alice");
+    let bob = ObjectRef::new("user", "bob");
+    let readme = ObjectRef::new("doc", "readme");
+    let guide = ObjectRef::new("doc", "guide");
+
+    // ##### Act & Assert ##### //
+    assert!(
+        relations.check(&readme, "viewer", &alice).await.unwrap(),
+        "Member of a group viewing the parent folder should view the doc"
+    );
+    assert!(
+        relations.check(&guide, "viewer", &bob).await.unwrap(),
+        "Editor should be a viewer"
+    );
+    assert!(
+        !relations.check(&readme, "viewer", &bob).await.unwrap(),
+        "Unrelated user shouldn't view the doc"
+    );
+
+    store.delete(&tuple("group:eng#member@user:alice"));
+    assert!(
+        !relations.check(&readme, "viewer", &alice).await.unwrap(),
+        "Deleted tuple shouldn't grant anything anymore"
+    );
+}
+
+#[tokio::test]
+async fn relation_depth_test() {
+    // ##### Arrange ##### //
+    // Groups nested deeper than the limit, each one a member of the next
+    let store = InMemoryTupleStore::new();
+    for level in 0..=MAX_RELATION_DEPTH {
+        store.write(tuple(&format!(
+            "group:g{level}#member@group:g{}#member",
+            level + 1
+        )));
+    }
+    let relations = Relations::new(store);
+
+    // ##### Act ##### //
+    let result = relations
+        .check(
+            &ObjectRef::new("group", "g0"),
+            "member",
+            &ObjectRef::new("user", "alice"),
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(
+            result,
+            Err(Error::RelationDepthExceeded(MAX_RELATION_DEPTH))
+        ),
+        "Relation depth should be told apart from reference depth"
+    );
+}
+
+#[tokio::test]
+async fn relation_cycle_test() {
+    // ##### Arrange ##### //
+    let store = Arc::new(InMemoryTupleStore::new());
+    store.write(tuple("group:eng#member@group:eng#member"));
+    store.write(tuple("group:eng#member@user:bob"));
+    // Assume a misconfigured schema where viewers and editors are computed from each other
+    let relations = Relations::new(store)
+        .with_rewrite("doc", "viewer", Rewrite::Computed("editor".into()))
+        .with_rewrite("doc", "editor", Rewrite::Computed("viewer".into()));
+    let alice = ObjectRef::new("user", "alice");
+    let eng = ObjectRef::new("group", "eng");
+
+    // ##### Act ##### //
+    let self_member = relations.check(&eng, "member", &alice).await;
+    let member = relations
+        .check(&eng, "member", &ObjectRef::new("user", "bob"))
+        .await;
+    let computed = relations
+        .check(&ObjectRef::new("doc", "readme"), "viewer", &alice)
+        .await;
+
+    // ##### Assert ##### //
+    assert!(
+        !self_member.unwrap(),
+        "Group containing itself shouldn't grant anything"
+    );
+    assert!(member.unwrap(), "Other members should still be found");
+    assert!(
+        !computed.unwrap(),
+        "Relations computed from each other shouldn't grant anything"
+    );
+}
+
+#[derive(Entity, Default)]
+struct User {
+    active: bool,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self { active: true }) })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Doc {
+    title: String,
+}
+
+impl EntityAdapter for Doc {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                title: "README".into(),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn evaluate_relation_test() {
+    // ##### Arrange ##### //
+    let store = Arc::new(InMemoryTupleStore::new());
+    store.write(tuple(&format!("doc:{README}#editor@user:{ALICE}")));
+
+    let engine = Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Doc>("doc")
+        .with_relations(relations(store))
+        .with_policy(
+            "doc.view",
+            has_relation(Target::Subject, "viewer", Target::Object),
+        );
+
+    let rules = Rules::new()
+        .and(has_relation(Target::Subject, "viewer", Target::Object))
+        .and(Rule {
+            left: SideRule::Subject("active".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::Bool(true)),
+        });
+
+    // ##### Act ##### //
+    let viewer = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(ALICE)),
+            EvaluateEntity::new("doc", Some(README)),
+            &rules,
+        )
+        .await;
+    let stranger = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::max())),
+            EvaluateEntity::new("doc", Some(README)),
+            &rules,
+        )
+        .await;
+    let through_policy = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(ALICE)),
+            EvaluateEntity::new("doc", Some(README)),
+            &policy("doc.view").into(),
+        )
+        .await;
+    let without_engine = evaluate(&User { active: true }, &Doc::default(), &rules);
+
+    // ##### Assert ##### //
+    assert!(viewer.unwrap(), "Editor should pass as viewer");
+    assert!(!stranger.unwrap(), "Stranger shouldn't pass");
+    assert!(
+        through_policy.unwrap(),
+        "Relation of a referenced policy should be checked too"
+    );
+    assert!(
+        matches!(without_engine, Err(Error::RelationsNotFound)),
+        "Relation can't be checked without relations"
+    );
+}
+
+#[test]
+fn deserialize_relation_test() {
+    // ##### Arrange ##### //
+    let json_rules = r#"
+        [[
+            { "HasRelation": { "subject": "Subject", "relation": "viewer", "object": "Object" } },
+            { "left": { "Subject": "active" }, "operator": "Equal", "right": { "Literal": true } }
+        ]]
+        "#;
+
+    // ##### Act ##### //
+    let rules: Rules = serde_json::from_str(json_rules).expect("Should deserialize Rules");
+
+    // ##### Assert ##### //
+    assert!(matches!(
+        rules.0[0][0],
+        Condition::HasRelation(ref rule) if rule.relation == "viewer"
+    ));
+    assert!(matches!(rules.0[0][1], Condition::Compare(_)));
+}
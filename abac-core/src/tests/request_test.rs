@@ -0,0 +1,207 @@
+use std::{path::PathBuf, sync::Arc};
+
+use uuid::Uuid;
+
+use super::common::User;
+use crate::{
+    Engine, Entity, EntityAdapter, Error, EvaluateEntity, EvaluateRequest, InMemoryTupleStore,
+    LoadResult, Relations, Rules, Target, entity, has_relation, object, subject,
+};
+
+const ROOT: Uuid = Uuid::from_u128(1);
+const DOCS: Uuid = Uuid::from_u128(2);
+const ACME: Uuid = Uuid::from_u128(3);
+const WISZEL: Uuid = Uuid::from_u128(4);
+
+#[derive(Entity, Default)]
+struct Folder {
+    owner: String,
+}
+
+impl EntityAdapter for Folder {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(id: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                owner: if id == ROOT { "WiszeL" } else { "bob" }.into(),
+            })
+        })
+    }
+}
+
+#[derive(Entity, Default)]
+struct Tenant {
+    plan: String,
+}
+
+impl EntityAdapter for Tenant {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                plan: "enterprise".into(),
+            })
+        })
+    }
+}
+
+fn engine() -> Engine {
+    Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .register_adapter::<Folder>("folder")
+        .register_adapter::<Tenant>("tenant")
+}
+
+fn request() -> EvaluateRequest<'static> {
+    EvaluateRequest::new(
+        EvaluateEntity::new("user", Some(WISZEL)),
+        EvaluateEntity::new("folder", Some(DOCS)),
+    )
+    .with_entity("parent", EvaluateEntity::new("folder", Some(ROOT)))
+    .with_entity("tenant", EvaluateEntity::new("tenant", Some(ACME)))
+}
+
+#[tokio::test]
+async fn evaluate_request_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let parent_owner = Rules::new()
+        .and(entity("tenant", Tenant::PLAN).eq("enterprise".to_string()))
+        .and(subject(User::NAME).eq(entity("parent", Folder::OWNER)));
+    let owner = Rules::new()
+        .and(entity("tenant", Tenant::PLAN).eq("enterprise".to_string()))
+        .and(subject(User::NAME).eq(object(Folder::OWNER)));
+
+    // ##### Act ##### //
+    let parent_owner_result = engine.evaluate_request(request(), &parent_owner).await;
+    let owner_result = engine.evaluate_request(request(), &owner).await;
+
+    // ##### Assert ##### //
+    assert!(
+        parent_owner_result.unwrap(),
+        "Owner of the parent folder should pass"
+    );
+    assert!(!owner_result.unwrap(), "Bob owns the folder itself");
+}
+
+#[tokio::test]
+async fn unknown_alias_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let rules = Rules::from(entity("device", Tenant::PLAN).eq("enterprise".to_string()));
+
+    // ##### Act ##### //
+    let result = engine.evaluate_request(request(), &rules).await;
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(result, Err(Error::UnknownAlias(ref alias)) if alias == "device"),
+        "Alias missing from the request should fail"
+    );
+}
+
+#[tokio::test]
+async fn aliased_relation_test() {
+    // ##### Arrange ##### //
+    let store = Arc::new(InMemoryTupleStore::new());
+    store.write(
+        format!("tenant:{ACME}#member@user:{WISZEL}")
+            .parse()
+            .unwrap(),
+    );
+    let engine = engine().with_relations(Relations::new(store));
+    let rules = Rules::from(has_relation(
+        Target::Subject,
+        "member",
+        Target::Entity("tenant".into()),
+    ));
+
+    // ##### Act ##### //
+    let member = engine.evaluate_request(request(), &rules).await;
+    let outsider = engine
+        .evaluate_request(
+            EvaluateRequest::new(
+                EvaluateEntity::new("user", Some(Uuid::nil())),
+                EvaluateEntity::new("folder", Some(DOCS)),
+            )
+            .with_entity("tenant", EvaluateEntity::new("tenant", Some(ACME))),
+            &rules,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(member.unwrap(), "WiszeL is a member of the tenant");
+    assert!(!outsider.unwrap(), "Other users aren't members");
+}
+
+#[test]
+fn aliased_rules_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let json = r#"[[{ "left": { "Entity": ["tenant", "plan"] }, "operator": "Equal", "right": { "Literal": "enterprise" } }]]"#;
+
+    // ##### Act ##### //
+    let rules: Rules = serde_json::from_str(json).unwrap();
+    let invalid: Rules = serde_json::from_str(&json.replace("plan", "seats")).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        rules,
+        entity("tenant", Tenant::PLAN)
+            .eq("enterprise".to_string())
+            .into(),
+        "Should match the builder"
+    );
+    assert!(
+        engine.validate("user", "folder", &rules).is_ok(),
+        "Alias should be checked against the entity of the same name"
+    );
+    assert!(
+        matches!(
+            engine.validate("user", "folder", &invalid),
+            Err(Error::UnknownAttribute { ref path, .. }) if path == "seats"
+        ),
+        "Unknown attribute of the aliased entity should be invalid"
+    );
+}
+
+#[test]
+fn validate_request_test() {
+    // ##### Arrange ##### //
+    let engine = engine();
+    let request = request().with_entity("owner", EvaluateEntity::new("user", Some(WISZEL)));
+    let rules = entity("owner", User::NAME).eq(object(Folder::OWNER)).into();
+    let invalid = entity("owner", Tenant::PLAN)
+        .eq("enterprise".to_string())
+        .into();
+    let unknown = entity("device", Tenant::PLAN)
+        .eq("enterprise".to_string())
+        .into();
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate_request(&request, &rules).is_ok(),
+        "Alias should be checked against the entity it's loaded from"
+    );
+    assert!(
+        matches!(
+            engine.validate_request(&request, &invalid),
+            Err(Error::UnknownAttribute { ref entity, ref path }) if entity == "user" && path == "plan"
+        ),
+        "Attribute of another entity should be invalid for the alias"
+    );
+    assert!(
+        matches!(
+            engine.validate_request(&request, &unknown),
+            Err(Error::UnknownAlias(ref alias)) if alias == "device"
+        ),
+        "Alias missing from the request should be invalid"
+    );
+}
@@ -0,0 +1,352 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    AttributeResolver, AttributeType, Engine, Entity, EntityAdapter, EntityId, EvaluateEntity,
+    LoadResult, Operator, Rule, Rules, SideRule,
+};
+
+/// Assume it's the risk scoring service
+#[derive(Default)]
+struct RiskService {
+    calls: AtomicUsize,
+}
+
+struct RiskScore;
+
+impl AttributeResolver for RiskScore {
+    type Provider = RiskService;
+
+    fn resolve<'a>(
+        &'a self,
+        id: Option<&'a EntityId>,
+        _: &'a dyn Entity,
+        service: &'a Self::Provider,
+    ) -> LoadResult<'a, Value> {
+        Box::pin(async move {
+            service.calls.fetch_add(1, Ordering::SeqCst);
+
+            let score = if id == Some(&Uuid::nil().into()) {
+                10
+            } else {
+                90
+            };
+
+            Ok(Value::U64(score))
+        })
+    }
+
+    fn attribute_type(&self) -> AttributeType {
+        AttributeType::Integer
+    }
+}
+
+#[derive(Entity, Default)]
+struct User {
+    name: String,
+}
+
+impl EntityAdapter for User {
+    type Provider = RiskService;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                name: "WiszeL".into(),
+            })
+        })
+    }
+}
+
+fn calls(engine: &Engine) -> usize {
+    let service = engine.providers.values().next().unwrap();
+
+    service
+        .downcast_ref::<RiskService>()
+        .unwrap()
+        .calls
+        .load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn resolve_attribute_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(RiskService::default())
+        .register_adapter::<User>("user")
+        .register_resolver("user", "risk_score", RiskScore);
+
+    let low_risk = Rules(vec![
+        vec![
+            Rule {
+                left: SideRule::Subject("risk_score".parse().unwrap()),
+                operator: Operator::Less,
+                right: SideRule::Literal(Value::U64(50)),
+            }
+            .into(),
+        ],
+        vec![
+            Rule {
+                left: SideRule::Subject("risk_score".parse().unwrap()),
+                operator: Operator::GreaterEqual,
+                right: SideRule::Literal(Value::U64(0)),
+            }
+            .into(),
+        ],
+    ]);
+    let by_name = Rules(vec![vec![
+        Rule {
+            left: SideRule::Subject("name".parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("WiszeL".into())),
+        }
+        .into(),
+    ]]);
+
+    // ##### Act ##### //
+    let trusted = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::nil())),
+            EvaluateEntity::new("user", None),
+            &low_risk,
+        )
+        .await;
+    let risky = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::max())),
+            EvaluateEntity::new("user", None),
+            &low_risk,
+        )
+        .await;
+    let calls_before = calls(&engine);
+    let unused = engine
+        .evaluate(
+            EvaluateEntity::new("user", Some(Uuid::nil())),
+            EvaluateEntity::new("user", None),
+            &by_name,
+        )
+        .await;
+
+    // ##### Assert ##### //
+    assert!(trusted.unwrap(), "Low risk score should pass");
+    assert!(!risky.unwrap(), "High risk score shouldn't pass");
+    assert_eq!(calls_before, 2, "Should resolve once per evaluation");
+    assert!(
+        unused.unwrap(),
+        "Rule without resolved attribute should pass"
+    );
+    assert_eq!(
+        calls(&engine),
+        calls_before,
+        "Shouldn't resolve when the rules don't use it"
+    );
+}
+
+#[test]
+fn resolver_schema_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .register_adapter::<User>("user")
+        .register_resolver("user", "risk_score", RiskScore);
+    let rules = Rules::from(Rule {
+        left: SideRule::Subject("risk_score".parse().unwrap()),
+        operator: Operator::Less,
+        right: SideRule::Literal(Value::U64(50)),
+    });
+
+    // ##### Act ##### //
+    let schema = engine.schema();
+
+    // ##### Assert ##### //
+    assert!(
+        schema
+            .entity("user")
+            .unwrap()
+            .attributes
+            .iter()
+            .any(|attr| attr.name == "risk_score" && attr.ty == AttributeType::Integer),
+        "Resolved attribute should be in the schema"
+    );
+    assert!(
+        engine.validate("user", "user", &rules).is_ok(),
+        "Resolved attribute should be valid"
+    );
+}
+
+/// Assume it's the company directory, recording the fields of every load
+#[derive(Default)]
+struct Directory {
+    loads: Mutex<Vec<Option<Vec<String>>>>,
+}
+
+#[derive(Entity, Default)]
+struct Employee {
+    name: String,
+    email: String,
+}
+
+impl EntityAdapter for Employee {
+    type Provider = Directory;
+    type Id = Uuid;
+
+    fn load_data(_: Uuid, directory: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            directory.loads.lock().unwrap().push(None);
+
+            Ok(Self {
+                name: "WiszeL".into(),
+                email: "wiszel@example.com".into(),
+            })
+        })
+    }
+
+    fn load_fields<'a>(
+        _: Uuid,
+        fields: &'a [&'a str],
+        directory: &'a Self::Provider,
+    ) -> LoadResult<'a, Self> {
+        Box::pin(async move {
+            directory
+                .loads
+                .lock()
+                .unwrap()
+                .push(Some(fields.iter().map(ToString::to_string).collect()));
+
+            // Only the asked fields are loaded
+            Ok(Self {
+                email: match fields.contains(&"email") {
+                    true => "wiszel@example.com".into(),
+                    false => String::new(),
+                },
+                ..Default::default()
+            })
+        })
+    }
+}
+
+/// Domain of the employee's email, declaring the field it reads
+struct Domain;
+
+impl AttributeResolver for Domain {
+    type Provider = Directory;
+
+    fn resolve<'a>(
+        &'a self,
+        _: Option<&'a EntityId>,
+        entity: &'a dyn Entity,
+        _: &'a Self::Provider,
+    ) -> LoadResult<'a, Value> {
+        Box::pin(async move {
+            let email = match entity.get_attribute("email")?.as_deref() {
+                Some(Value::String(email)) => email.clone(),
+                _ => String::new(),
+            };
+            let domain = email.split_once('@').map(|(_, domain)| domain);
+
+            Ok(Value::String(domain.unwrap_or_default().to_string()))
+        })
+    }
+
+    fn fields(&self) -> Option<&'static [&'static str]> {
+        Some(&["email"])
+    }
+}
+
+/// Same as `Domain` without declaring the field it reads
+struct UndeclaredDomain;
+
+impl AttributeResolver for UndeclaredDomain {
+    type Provider = Directory;
+
+    fn resolve<'a>(
+        &'a self,
+        id: Option<&'a EntityId>,
+        entity: &'a dyn Entity,
+        directory: &'a Self::Provider,
+    ) -> LoadResult<'a, Value> {
+        Domain.resolve(id, entity, directory)
+    }
+}
+
+fn loads(engine: &Engine) -> Vec<Option<Vec<String>>> {
+    let directory = engine.providers.values().next().unwrap();
+
+    std::mem::take(
+        &mut directory
+            .downcast_ref::<Directory>()
+            .unwrap()
+            .loads
+            .lock()
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn resolver_projection_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(Directory::default())
+        .register_adapter::<Employee>("employee")
+        .register_resolver("employee", "domain", Domain)
+        .register_resolver("employee", "undeclared_domain", UndeclaredDomain);
+    let rules = |attribute: &str| {
+        Rules::from(Rule {
+            left: SideRule::Subject(attribute.parse().unwrap()),
+            operator: Operator::Equal,
+            right: SideRule::Literal(Value::String("example.com".into())),
+        })
+    };
+    let evaluate = |rules: Rules| {
+        let engine = &engine;
+
+        async move {
+            engine
+                .evaluate(
+                    EvaluateEntity::new("employee", Some(Uuid::nil())),
+                    EvaluateEntity::new("employee", None),
+                    &rules,
+                )
+                .await
+        }
+    };
+
+    // ##### Act ##### //
+    let declared = evaluate(rules("domain")).await;
+    let declared_loads = loads(&engine);
+    let undeclared = evaluate(rules("undeclared_domain")).await;
+    let undeclared_loads = loads(&engine);
+    let unused = evaluate(Rules::new()).await;
+    let unused_loads = loads(&engine);
+
+    // ##### Assert ##### //
+    assert!(
+        declared.unwrap(),
+        "Resolver should read the field it declares"
+    );
+    assert_eq!(
+        declared_loads,
+        vec![Some(vec!["email".to_string()])],
+        "Only the declared field should be loaded"
+    );
+    assert!(
+        undeclared.unwrap(),
+        "Resolver without declared fields should read the whole entity"
+    );
+    assert_eq!(
+        undeclared_loads,
+        vec![None],
+        "Whole entity should be loaded"
+    );
+    assert!(unused.unwrap(), "Empty rules should pass");
+    assert!(
+        unused_loads.is_empty(),
+        "Entity nothing is read from shouldn't be loaded"
+    );
+}
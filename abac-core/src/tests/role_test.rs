@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use crate::{
+    Condition, Engine, Entity, EntityAdapter, EvaluateEntity, LoadResult, RoleGraph, Rules,
+    evaluate, has_permission, has_role,
+};
+
+fn roles() -> RoleGraph {
+    RoleGraph::new()
+        .inherit("admin", ["editor"])
+        .inherit("editor", ["viewer"])
+        .permit("viewer", ["task.view"])
+        .permit("editor", ["task.edit"])
+        .permit("admin", ["task.delete"])
+}
+
+#[derive(Entity, Default)]
+struct User {
+    roles: Vec<String>,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move {
+            Ok(Self {
+                roles: vec!["editor".into()],
+            })
+        })
+    }
+}
+
+#[test]
+fn role_graph_test() {
+    // ##### Arrange ##### //
+    let roles = roles().inherit("viewer", ["admin"]); // cycle shouldn't hang
+
+    // ##### Act & Assert ##### //
+    assert!(
+        roles.has_role(["admin"], "viewer"),
+        "Admin should be viewer"
+    );
+    assert!(
+        roles.has_permission(["editor"], "task.view"),
+        "Editor should inherit viewer permissions"
+    );
+    assert!(
+        roles.has_permission(["viewer"], "task.delete"),
+        "Cycle makes viewer an admin"
+    );
+    assert!(
+        !RoleGraph::new().has_role(["admin"], "viewer"),
+        "No inheritance without graph"
+    );
+}
+
+#[tokio::test]
+async fn evaluate_role_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new()
+        .with_provider(PathBuf::new())
+        .register_adapter::<User>("user")
+        .with_roles(roles());
+    let evaluate_role = async |rules: Rules| {
+        engine
+            .evaluate(
+                EvaluateEntity::new("user", Some(Uuid::nil())),
+                EvaluateEntity::new("user", None),
+                &rules,
+            )
+            .await
+            .unwrap()
+    };
+
+    // ##### Act & Assert ##### //
+    assert!(
+        evaluate_role(has_role(User::ROLES, "viewer").into()).await,
+        "Editor should have viewer role"
+    );
+    assert!(
+        !evaluate_role(has_role(User::ROLES, "admin").into()).await,
+        "Editor shouldn't have admin role"
+    );
+    assert!(
+        evaluate_role(has_permission(User::ROLES, "task.edit").into()).await,
+        "Editor should be able to edit"
+    );
+    assert!(
+        !evaluate_role(has_permission(User::ROLES, "task.delete").into()).await,
+        "Editor shouldn't be able to delete"
+    );
+}
+
+#[test]
+fn evaluate_flat_role_test() {
+    // ##### Arrange ##### //
+    let user = User {
+        roles: vec!["admin".into()],
+    };
+    let rules = Rules::new().and_any([
+        has_role(User::ROLES, "admin"),
+        has_role(User::ROLES, "owner"),
+    ]);
+
+    // ##### Act ##### //
+    let result = evaluate(&user, &User::default(), &rules);
+
+    // ##### Assert ##### //
+    assert!(
+        result.unwrap(),
+        "Role should match directly without a graph"
+    );
+}
+
+#[test]
+fn deserialize_role_test() {
+    // ##### Arrange ##### //
+    let json_rules = r#"
+        [
+            [{ "HasRole": { "attribute": "roles", "role": "editor" } }],
+            [{ "HasPermission": { "attribute": "roles", "permission": "task.edit" } }]
+        ]
+        "#;
+
+    // ##### Act ##### //
+    let rules: Rules = serde_json::from_str(json_rules).expect("Should deserialize Rules");
+
+    // ##### Assert ##### //
+    assert!(matches!(rules.0[0][0], Condition::HasRole(ref rule) if rule.role == "editor"));
+    assert!(matches!(
+        rules.0[1][0],
+        Condition::HasPermission(ref rule) if rule.permission == "task.edit"
+    ));
+}
@@ -1,6 +1,6 @@
 use serde_value::Value;
 
-use crate::{Operator, Rule, SideRule};
+use crate::{Operator, Rule, Rules, SideRule};
 
 #[test]
 fn rule_01_equal_subject_vs_object() {
@@ -111,3 +111,42 @@ fn rule_05_less_equal_subject_vs_literal() {
                          SideRule::Literal(ref v) if *v == Value::U64(1000)));
     assert!(matches!(rule.operator, Operator::LessEqual));
 }
+
+#[test]
+fn rules_error_test() {
+    // ##### Arrange ##### //
+    let rules = |rule: &str| {
+        format!(
+            r#"[[{{ "Named": "is_owner" }}, {rule}], [{{ "Any": {{ "collection": {{ "Subject": "members" }}, "rules": [[{rule}]] }} }}]]"#
+        )
+    };
+    let cidr = r#"{ "left": { "Subject": "ip" }, "operator": "InCidr", "right": { "Literal": "10.0.0.0/40" } }"#;
+    let path = r#"{ "left": { "Subject": "a..b" }, "operator": "Equal", "right": { "Literal": 1 } }"#;
+
+    // ##### Act ##### //
+    let valid = serde_json::from_str::<Rules>(&rules(&cidr.replace("/40", "/8")));
+    let invalid_cidr = serde_json::from_str::<Rules>(&rules(cidr));
+    let invalid_path = serde_json::from_str::<Rules>(&rules(path));
+    let invalid_tagged = serde_json::from_str::<Rules>(r#"[[{ "Named": 1 }]]"#);
+
+    // ##### Assert ##### //
+    assert!(valid.is_ok(), "Valid rules should deserialize");
+    assert!(
+        invalid_cidr
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid CIDR block: 10.0.0.0/40"),
+        "CIDR error should be kept inside Rules"
+    );
+    assert!(
+        invalid_path
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid attribute path: a..b"),
+        "Path error should be kept inside Rules"
+    );
+    assert!(
+        invalid_tagged.is_err(),
+        "Invalid tagged condition should be rejected"
+    );
+}
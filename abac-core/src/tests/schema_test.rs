@@ -0,0 +1,162 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::Serialize;
+#[cfg(feature = "json-schema")]
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{AttributeSchema, AttributeType, Engine, Entity, EntityAdapter, LoadResult};
+
+#[derive(Entity, Default, Serialize)]
+struct Address {
+    country: String,
+}
+
+#[derive(Entity, Default)]
+struct User {
+    age: u64,
+    nickname: Option<String>,
+    roles: Vec<String>,
+    labels: HashMap<String, String>,
+    address: Address,
+    path: PathBuf,
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+#[test]
+fn attributes_test() {
+    // ##### Act ##### //
+    let attributes = User::default().attributes();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        attributes,
+        vec![
+            AttributeSchema::new("age", AttributeType::Integer),
+            AttributeSchema::new(
+                "nickname",
+                AttributeType::Optional(Box::new(AttributeType::String))
+            ),
+            AttributeSchema::new(
+                "roles",
+                AttributeType::List(Box::new(AttributeType::String))
+            ),
+            AttributeSchema::new(
+                "labels",
+                AttributeType::Map(Box::new(AttributeType::String))
+            ),
+            AttributeSchema::new(
+                "address",
+                AttributeType::Struct(vec![AttributeSchema::new("country", AttributeType::String)])
+            ),
+            AttributeSchema::new("path", AttributeType::Any),
+        ],
+        "Should describe every field's type"
+    );
+}
+
+#[test]
+fn attribute_type_test() {
+    // ##### Arrange ##### //
+    let user = User::default();
+
+    // ##### Act ##### //
+    let types: Vec<_> = user
+        .attributes()
+        .into_iter()
+        .map(|attr| (user.attribute_type(&attr.name), attr.ty))
+        .collect();
+    let missing = user.attribute_type("email");
+
+    // ##### Assert ##### //
+    for (ty, expected) in types {
+        assert_eq!(ty, Some(expected), "Should match the type in the schema");
+    }
+    assert_eq!(missing, None, "Unknown field should have no type");
+}
+
+#[test]
+fn engine_schema_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<User>("user");
+
+    // ##### Act ##### //
+    let schema = engine.schema();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        schema.entities.len(),
+        1,
+        "Should describe registered entity"
+    );
+    assert!(
+        schema.entity("user").is_some(),
+        "Should find entity by name"
+    );
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn json_schema_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<User>("user");
+
+    // ##### Act ##### //
+    let json_schema = engine.schema().to_json_schema();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        json_schema["$defs"]["user"]["properties"]["age"],
+        json!({ "type": "integer" }),
+        "Integer should be exported as JSON Schema integer"
+    );
+    assert_eq!(
+        json_schema["$defs"]["user"]["required"],
+        json!(["age", "roles", "labels", "address", "path"]),
+        "Optional field shouldn't be required"
+    );
+}
+
+#[derive(Entity, Serialize)]
+struct Folder {
+    name: String,
+    parent: Option<Box<Folder>>,
+    children: Vec<Folder>,
+}
+
+#[test]
+fn recursive_type_test() {
+    // ##### Act ##### //
+    let attributes = Folder {
+        name: "root".into(),
+        parent: None,
+        children: Vec::new(),
+    }
+    .attributes();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        attributes,
+        vec![
+            AttributeSchema::new("name", AttributeType::String),
+            AttributeSchema::new(
+                "parent",
+                AttributeType::Optional(Box::new(AttributeType::Any))
+            ),
+            AttributeSchema::new(
+                "children",
+                AttributeType::List(Box::new(AttributeType::Any))
+            ),
+        ],
+        "Struct nested inside itself should stop at Any"
+    );
+}
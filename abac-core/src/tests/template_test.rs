@@ -0,0 +1,268 @@
+use std::{net::IpAddr, path::PathBuf};
+
+use serde::{Serialize, Serializer, ser::Error as _};
+use uuid::Uuid;
+
+use crate::{
+    AttributeType, Cidr, Engine, Entity, EntityAdapter, Error, LoadResult, Operator, Params, Rule,
+    Rules, SideRule, Template, evaluate, param, subject,
+};
+
+#[derive(Entity)]
+struct User {
+    level: u8,
+    region: String,
+    ip: IpAddr,
+}
+
+impl Default for User {
+    fn default() -> Self {
+        Self {
+            level: 3,
+            region: "eu".into(),
+            ip: "10.1.2.3".parse().unwrap(),
+        }
+    }
+}
+
+impl EntityAdapter for User {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+/// Parameter value that can't be serialized
+struct Broken;
+
+impl Serialize for Broken {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom("broken"))
+    }
+}
+
+fn template() -> Template {
+    Template::new(
+        Rules::new()
+            .and(subject(User::LEVEL).ge(param("min_level")))
+            .and(subject(User::REGION).eq(param("region"))),
+    )
+    .param::<u8>("min_level")
+    .param::<String>("region")
+}
+
+#[test]
+fn instantiate_test() {
+    // ##### Arrange ##### //
+    let template = template();
+    let user = User::default();
+
+    // ##### Act ##### //
+    let basic = template
+        .instantiate(
+            &Params::new()
+                .bind("min_level", 1u8)
+                .unwrap()
+                .bind("region", "eu")
+                .unwrap(),
+        )
+        .unwrap();
+    let premium = template
+        .instantiate(
+            &Params::new()
+                .bind("min_level", 5)
+                .unwrap()
+                .bind("region", "eu")
+                .unwrap(),
+        )
+        .unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        basic,
+        Rules::new()
+            .and(subject(User::LEVEL).ge(1))
+            .and(subject(User::REGION).eq("eu".to_string())),
+        "Placeholders should be replaced by the values"
+    );
+    assert!(
+        evaluate(&user, &user, &basic).unwrap(),
+        "Level 3 should pass the basic threshold"
+    );
+    assert!(
+        !evaluate(&user, &user, &premium).unwrap(),
+        "Level 3 shouldn't pass the premium threshold"
+    );
+}
+
+#[test]
+fn instantiate_error_test() {
+    // ##### Arrange ##### //
+    let template = template();
+
+    // ##### Act ##### //
+    let unbound = template.instantiate(&Params::new().bind("min_level", 1).unwrap());
+    let unknown = template.instantiate(
+        &Params::new()
+            .bind("min_level", 1)
+            .unwrap()
+            .bind("region", "eu")
+            .unwrap()
+            .bind("max_level", 9)
+            .unwrap(),
+    );
+    let invalid = template.instantiate(
+        &Params::new()
+            .bind("min_level", "high")
+            .unwrap()
+            .bind("region", "eu")
+            .unwrap(),
+    );
+    let unserializable = Params::new().bind("region", Broken);
+    let uninstantiated = evaluate(&User::default(), &User::default(), &template.rules);
+
+    // ##### Assert ##### //
+    assert!(
+        matches!(unbound, Err(Error::UnboundParameter(ref name)) if name == "region"),
+        "Every placeholder should be bound"
+    );
+    assert!(
+        matches!(unknown, Err(Error::UnknownParameter(ref name)) if name == "max_level"),
+        "Only declared placeholders should be bound"
+    );
+    assert!(
+        matches!(invalid, Err(Error::InvalidParameter { ref name, .. }) if name == "min_level"),
+        "Value should have the placeholder's type"
+    );
+    assert!(
+        matches!(unserializable, Err(Error::SerializationError(_))),
+        "Value that can't be serialized shouldn't be bound"
+    );
+    assert!(
+        matches!(uninstantiated, Err(Error::UnboundParameter(_))),
+        "Template rules can't be evaluated as they are"
+    );
+}
+
+#[test]
+fn cidr_param_test() {
+    // ##### Arrange ##### //
+    let template = Template::new(Rule {
+        left: SideRule::Subject("ip".parse().unwrap()),
+        operator: Operator::InCidr,
+        right: SideRule::Param("allowed".into()),
+    })
+    .param_type(
+        "allowed",
+        AttributeType::List(Box::new(AttributeType::Cidr)),
+    );
+    let user = User::default();
+
+    // ##### Act ##### //
+    let rules = template
+        .instantiate(
+            &Params::new()
+                .bind("allowed", ["10.0.0.0/8", "192.168.0.0/16"])
+                .unwrap(),
+        )
+        .unwrap();
+    let invalid = template.instantiate(&Params::new().bind("allowed", ["10.0.0.0/33"]).unwrap());
+
+    // ##### Assert ##### //
+    assert_eq!(
+        rules,
+        subject(User::IP)
+            .in_cidr([
+                "10.0.0.0/8".parse::<Cidr>().unwrap(),
+                "192.168.0.0/16".parse().unwrap(),
+            ])
+            .into(),
+        "Bound blocks should be parsed"
+    );
+    assert!(
+        evaluate(&user, &user, &rules).unwrap(),
+        "Should be in the block"
+    );
+    assert!(
+        matches!(invalid, Err(Error::InvalidCidr(_))),
+        "Invalid block should fail when instantiated"
+    );
+}
+
+#[test]
+fn validate_template_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<User>("user");
+    let mistyped = Template::new(subject(User::LEVEL).ge(subject(User::LEVEL) * param("factor")))
+        .param::<String>("factor");
+    let undeclared = Template::new(subject(User::LEVEL).gt(param::<u8>("min_level")));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine
+            .validate_template("user", "user", &template())
+            .is_ok(),
+        "Template should be valid"
+    );
+    assert!(
+        template().check().is_ok(),
+        "Every placeholder should be declared"
+    );
+    assert!(
+        matches!(
+            engine.validate_template("user", "user", &mistyped),
+            Err(Error::InvalidOperands { .. })
+        ),
+        "Placeholder should be checked with its declared type"
+    );
+    assert!(
+        matches!(undeclared.check(), Err(Error::UnknownParameter(ref name)) if name == "min_level"),
+        "Undeclared placeholder should be reported"
+    );
+    assert!(
+        matches!(
+            engine.validate_template("user", "user", &undeclared),
+            Err(Error::UnknownParameter(_))
+        ),
+        "Undeclared placeholder should be invalid"
+    );
+    assert!(
+        matches!(
+            engine.validate("user", "user", &template().rules),
+            Err(Error::UnboundParameter(_))
+        ),
+        "Template rules aren't valid rules"
+    );
+}
+
+#[test]
+fn template_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"{
+        "params": { "min_level": "Integer", "regions": { "List": "String" } },
+        "rules": [
+            [{ "left": { "Subject": "level" }, "operator": "GreaterEqual", "right": { "Param": "min_level" } }],
+            [{ "Any": { "collection": { "Param": "regions" }, "rules": [[{ "left": { "Element": null }, "operator": "Equal", "right": { "Subject": "region" } }]] } }]
+        ]
+    }"#;
+    let params = r#"{ "min_level": 2, "regions": ["us", "eu"] }"#;
+    let user = User::default();
+
+    // ##### Act ##### //
+    let template: Template = serde_json::from_str(json).unwrap();
+    let params: Params = serde_json::from_str(params).unwrap();
+    let rules = template.instantiate(&params).unwrap();
+
+    // ##### Assert ##### //
+    assert!(
+        template.check().is_ok(),
+        "Every placeholder should be declared"
+    );
+    assert!(
+        evaluate(&user, &user, &rules).unwrap(),
+        "Should pass for the eu region"
+    );
+}
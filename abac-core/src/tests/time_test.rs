@@ -0,0 +1,384 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::Serialize;
+use serde_value::Value;
+use uuid::Uuid;
+
+use crate::{
+    AttributeSchema, AttributeType, Duration, Engine, Entity, EntityAdapter, Error, EvaluateEntity,
+    FixedClock, FunctionCall, LoadResult, Operator, Rule, Rules, Schedule, SideRule, evaluate,
+    object, subject,
+};
+
+#[derive(Entity, Serialize)]
+struct Session {
+    started_at: DateTime<Utc>,
+    expires_at: DateTime<FixedOffset>,
+    birthday: NaiveDate,
+    timeout: Duration,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            started_at: at("2025-06-02T10:00:00Z"),
+            // Same instant as 2025-06-02T12:00:00Z
+            expires_at: DateTime::parse_from_rfc3339("2025-06-02T19:00:00+07:00").unwrap(),
+            birthday: NaiveDate::from_ymd_opt(2000, 1, 31).unwrap(),
+            timeout: Duration::minutes(90),
+        }
+    }
+}
+
+impl EntityAdapter for Session {
+    type Provider = PathBuf;
+    type Id = Uuid;
+
+    // Assume it loads data from database
+    fn load_data(_: Uuid, _: &Self::Provider) -> LoadResult<'_, Self> {
+        Box::pin(async move { Ok(Self::default()) })
+    }
+}
+
+fn at(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+fn literal(value: &str) -> SideRule {
+    SideRule::Literal(Value::String(value.into()))
+}
+
+#[test]
+fn duration_test() {
+    // ##### Act ##### //
+    let parsed = "1h30m".parse::<Duration>();
+    let days = "7d".parse::<Duration>();
+    let out_of_order = "30m1h".parse::<Duration>();
+    let no_unit = "90".parse::<Duration>();
+    let json = serde_json::to_string(&Duration::seconds(3_725)).unwrap();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        parsed.unwrap(),
+        Duration::minutes(90),
+        "Should add the units"
+    );
+    assert_eq!(days.unwrap(), Duration::days(7), "Should parse days");
+    assert!(
+        matches!(out_of_order, Err(Error::InvalidDuration(_))),
+        "Units should be in descending order"
+    );
+    assert!(
+        matches!(no_unit, Err(Error::InvalidDuration(_))),
+        "Amount should have a unit"
+    );
+    assert_eq!(json, r#""1h2m5s""#, "Should serialize as a string");
+}
+
+#[test]
+fn temporal_attributes_test() {
+    // ##### Act ##### //
+    let attributes = Session::default().attributes();
+
+    // ##### Assert ##### //
+    assert_eq!(
+        attributes,
+        vec![
+            AttributeSchema::new("started_at", AttributeType::Timestamp),
+            AttributeSchema::new("expires_at", AttributeType::Timestamp),
+            AttributeSchema::new("birthday", AttributeType::Date),
+            AttributeSchema::new("timeout", AttributeType::Duration),
+        ],
+        "Temporal fields should have temporal types"
+    );
+}
+
+#[test]
+fn temporal_operators_test() {
+    // ##### Arrange ##### //
+    let session = Session::default();
+
+    let before = Rules::new().and(subject(Session::STARTED_AT).before(object(Session::EXPIRES_AT)));
+    let after = Rules::new().and(subject(Session::STARTED_AT).after(object(Session::EXPIRES_AT)));
+    let within = Rules::new()
+        .and(subject(Session::STARTED_AT).within(Duration::hours(2), object(Session::EXPIRES_AT)));
+    let not_within = Rules::new().and(
+        subject(Session::STARTED_AT).within(Duration::minutes(119), object(Session::EXPIRES_AT)),
+    );
+    let birthday = Rules::new().and(Rule {
+        left: SideRule::Subject("birthday".parse().unwrap()),
+        operator: Operator::Before,
+        right: literal("2000-02-01T00:00:00Z"),
+    });
+    let invalid = Rules::new().and(Rule {
+        left: SideRule::Subject("timeout".parse().unwrap()),
+        operator: Operator::After,
+        right: literal("2000-02-01"),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        evaluate(&session, &session, &before).unwrap(),
+        "Should compare instants regardless of the offset"
+    );
+    assert!(
+        !evaluate(&session, &session, &after).unwrap(),
+        "Start shouldn't be after expiry"
+    );
+    assert!(
+        evaluate(&session, &session, &within).unwrap(),
+        "2 hours apart should be within 2 hours"
+    );
+    assert!(
+        !evaluate(&session, &session, &not_within).unwrap(),
+        "2 hours apart shouldn't be within 119 minutes"
+    );
+    assert!(
+        evaluate(&session, &session, &birthday).unwrap(),
+        "Date should compare with a timestamp"
+    );
+    assert!(
+        matches!(
+            evaluate(&session, &session, &invalid),
+            Err(Error::InvalidOperands { .. })
+        ),
+        "Duration isn't a timestamp"
+    );
+}
+
+#[test]
+fn chronological_compare_test() {
+    // ##### Arrange ##### //
+    let session = Session::default();
+
+    // Lexically "2025-06-02T19:00:00+07:00" > "2025-06-02T13:00:00Z"
+    let earlier = Rules::new().and(Rule {
+        left: SideRule::Subject("expires_at".parse().unwrap()),
+        operator: Operator::Less,
+        right: literal("2025-06-02T13:00:00Z"),
+    });
+    // Lexically "1h30m" < "45m"
+    let longer = Rules::new().and(Rule {
+        left: SideRule::Subject("timeout".parse().unwrap()),
+        operator: Operator::Greater,
+        right: literal("45m"),
+    });
+    let same = Rules::new().and(Rule {
+        left: SideRule::Subject("expires_at".parse().unwrap()),
+        operator: Operator::Equal,
+        right: literal("2025-06-02T12:00:00Z"),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        evaluate(&session, &session, &earlier).unwrap(),
+        "Timestamps should compare chronologically"
+    );
+    assert!(
+        evaluate(&session, &session, &longer).unwrap(),
+        "Durations should compare by length"
+    );
+    assert!(
+        evaluate(&session, &session, &same).unwrap(),
+        "Same instant in another offset should be equal"
+    );
+}
+
+#[test]
+fn plain_string_compare_test() {
+    // ##### Arrange ##### //
+    // Attributes of an unknown type, eg. free-form labels
+    let labels = HashMap::from([
+        ("ttl".to_string(), Value::String("1h".into())),
+        ("release".to_string(), Value::String("2024-01-01".into())),
+    ]);
+    let compare = |attribute: &str, value: &str| {
+        Rules::new().and(Rule {
+            left: SideRule::Subject(attribute.parse().unwrap()),
+            operator: Operator::Equal,
+            right: literal(value),
+        })
+    };
+
+    // ##### Act & Assert ##### //
+    assert!(
+        !evaluate(&labels, &labels, &compare("ttl", "60m")).unwrap(),
+        "Strings that look like durations should compare as strings"
+    );
+    assert!(
+        !evaluate(&labels, &labels, &compare("release", "2024-01-01T00:00:00")).unwrap(),
+        "Strings that look like timestamps should compare as strings"
+    );
+    assert!(
+        evaluate(&labels, &labels, &compare("ttl", "1h")).unwrap(),
+        "Same strings should be equal"
+    );
+}
+
+#[tokio::test]
+async fn clock_test() {
+    // ##### Arrange ##### //
+    // Monday
+    let engine = |now| {
+        Engine::new()
+            .with_provider(PathBuf::new())
+            .register_adapter::<Session>("session")
+            .with_clock(FixedClock(at(now)))
+    };
+    let evaluate = |engine: Engine, rules: Rules| async move {
+        engine
+            .evaluate(
+                EvaluateEntity::new("session", Some(Uuid::nil())),
+                EvaluateEntity::new("session", Some(Uuid::nil())),
+                &rules,
+            )
+            .await
+    };
+
+    let now = || {
+        SideRule::Function(FunctionCall {
+            name: "now".into(),
+            args: Vec::new(),
+        })
+    };
+    let active = Rules::new()
+        .and(Rule {
+            left: SideRule::Object("started_at".parse().unwrap()),
+            operator: Operator::Before,
+            right: now(),
+        })
+        .and(Rule {
+            left: now(),
+            operator: Operator::Before,
+            right: SideRule::Object("expires_at".parse().unwrap()),
+        });
+
+    // ##### Act ##### //
+    let during = evaluate(engine("2025-06-02T11:00:00Z"), active.clone()).await;
+    let expired = evaluate(engine("2025-06-02T13:00:00Z"), active).await;
+
+    // ##### Assert ##### //
+    assert!(
+        during.unwrap(),
+        "Session should be active at the clock's time"
+    );
+    assert!(
+        !expired.unwrap(),
+        "Session should expire at the clock's time"
+    );
+}
+
+#[tokio::test]
+async fn schedule_test() {
+    // ##### Arrange ##### //
+    let engine = |now| {
+        Engine::new()
+            .with_provider(PathBuf::new())
+            .register_adapter::<Session>("session")
+            .with_clock(FixedClock(at(now)))
+    };
+    let evaluate = |engine: Engine, rules: Rules| async move {
+        engine
+            .evaluate(
+                EvaluateEntity::new("session", Some(Uuid::nil())),
+                EvaluateEntity::new("session", Some(Uuid::nil())),
+                &rules,
+            )
+            .await
+            .unwrap()
+    };
+
+    let business_hours = Rules::from(Schedule::business_hours());
+    let jakarta = Rules::from(
+        Schedule::business_hours().with_offset(FixedOffset::east_opt(7 * 3_600).unwrap()),
+    );
+    let night_shift = Rules::from(Schedule::new(
+        NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+    ));
+
+    // ##### Act & Assert ##### //
+    assert!(
+        evaluate(engine("2025-06-02T10:00:00Z"), business_hours.clone()).await,
+        "Monday morning should be in business hours"
+    );
+    assert!(
+        !evaluate(engine("2025-06-07T10:00:00Z"), business_hours.clone()).await,
+        "Saturday shouldn't be in business hours"
+    );
+    assert!(
+        !evaluate(engine("2025-06-02T17:00:00Z"), business_hours).await,
+        "End of business hours should be excluded"
+    );
+    assert!(
+        evaluate(engine("2025-06-02T03:00:00Z"), jakarta).await,
+        "03:00 UTC is 10:00 in Jakarta"
+    );
+    assert!(
+        evaluate(engine("2025-06-03T02:00:00Z"), night_shift).await,
+        "Schedule should wrap past midnight"
+    );
+}
+
+#[test]
+fn temporal_serde_test() {
+    // ##### Arrange ##### //
+    let json = r#"[
+        [{ "During": { "days": ["Mon", "Fri"], "start": "09:00:00", "end": "17:00:00", "offset": "+07:00" } }],
+        [{ "left": { "Subject": "started_at" }, "operator": { "Within": "1h30m" }, "right": { "Object": "expires_at" } }]
+    ]"#;
+
+    // ##### Act ##### //
+    let rules: Rules = serde_json::from_str(json).unwrap();
+    let invalid = serde_json::from_str::<Rules>(
+        r#"[[{ "left": { "Subject": "a" }, "operator": { "Within": "soon" }, "right": { "Object": "b" } }]]"#,
+    );
+
+    // ##### Assert ##### //
+    assert_eq!(
+        rules,
+        Rules::new()
+            .and(
+                Schedule::new(
+                    NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                    NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+                )
+                .on([Weekday::Mon, Weekday::Fri])
+                .with_offset(FixedOffset::east_opt(7 * 3_600).unwrap())
+            )
+            .and(Rule {
+                left: SideRule::Subject("started_at".parse().unwrap()),
+                operator: Operator::Within(Duration::minutes(90)),
+                right: SideRule::Object("expires_at".parse().unwrap()),
+            }),
+        "Schedule and duration should deserialize"
+    );
+    assert!(invalid.is_err(), "Invalid duration should be rejected");
+}
+
+#[test]
+fn validate_temporal_test() {
+    // ##### Arrange ##### //
+    let engine = Engine::new().register_adapter::<Session>("session");
+
+    let valid = Rules::new().and(subject(Session::BIRTHDAY).before(object(Session::BIRTHDAY)));
+    let invalid = Rules::new().and(Rule {
+        left: SideRule::Subject("started_at".parse().unwrap()),
+        operator: Operator::After,
+        right: SideRule::Literal(Value::Bool(true)),
+    });
+
+    // ##### Act & Assert ##### //
+    assert!(
+        engine.validate("session", "session", &valid).is_ok(),
+        "Dates should be valid temporal operands"
+    );
+    assert!(
+        matches!(
+            engine.validate("session", "session", &invalid),
+            Err(Error::InvalidOperands { .. })
+        ),
+        "Bool isn't a timestamp"
+    );
+}
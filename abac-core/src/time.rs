@@ -0,0 +1,320 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc, Weekday,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_value::Value;
+
+use crate::{Attribute, AttributeType, Error, Number, unwrap_optional, unwrap_value};
+
+/// Source of the current time for `now()` and schedules, swap it on the `Engine` for deterministic tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Reads the system time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Always at the same time
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Length of time, written as units in descending order, eg. `"90s"`, `"15m"`, `"1h30m"`, `"7d"`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(TimeDelta);
+
+impl Duration {
+    pub const fn seconds(seconds: i64) -> Self {
+        Self(TimeDelta::seconds(seconds))
+    }
+
+    pub const fn minutes(minutes: i64) -> Self {
+        Self(TimeDelta::minutes(minutes))
+    }
+
+    pub const fn hours(hours: i64) -> Self {
+        Self(TimeDelta::hours(hours))
+    }
+
+    pub const fn days(days: i64) -> Self {
+        Self(TimeDelta::days(days))
+    }
+
+    pub fn as_seconds(&self) -> i64 {
+        self.0.num_seconds()
+    }
+}
+
+impl From<TimeDelta> for Duration {
+    fn from(delta: TimeDelta) -> Self {
+        Self(delta)
+    }
+}
+
+impl From<Duration> for TimeDelta {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut seconds = self.0.num_seconds();
+        if seconds == 0 {
+            return f.write_str("0s");
+        }
+        if seconds < 0 {
+            f.write_str("-")?;
+            seconds = -seconds;
+        }
+
+        for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+            if seconds >= size {
+                write!(f, "{}{unit}", seconds / size)?;
+                seconds %= size;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Duration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidDuration(s.to_string());
+        let (negative, mut rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut seconds: i64 = 0;
+        let mut last_size = i64::MAX;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let (amount, unit) = rest.split_at(digits);
+            let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+            let size = match unit.chars().next() {
+                Some('d') => 86_400,
+                Some('h') => 3_600,
+                Some('m') => 60,
+                Some('s') => 1,
+                _ => return Err(invalid()),
+            };
+
+            // Each unit at most once, from the largest
+            if size >= last_size {
+                return Err(invalid());
+            }
+            last_size = size;
+
+            seconds = amount
+                .checked_mul(size)
+                .and_then(|amount| seconds.checked_add(amount))
+                .ok_or_else(invalid)?;
+            rest = &unit[1..];
+        }
+
+        let seconds = if negative { -seconds } else { seconds };
+        TimeDelta::try_seconds(seconds)
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Weekly time window in a fixed UTC offset, eg. business hours
+///
+/// An `end` before the `start` wraps past midnight, no `days` means every day
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub(crate) days: Vec<Weekday>,
+    pub(crate) start: NaiveTime,
+    pub(crate) end: NaiveTime,
+    #[serde(default = "utc", with = "offset")]
+    pub(crate) offset: FixedOffset,
+}
+
+impl Schedule {
+    /// Every day from `start` until `end`, in UTC
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            days: Vec::new(),
+            start,
+            end,
+            offset: utc(),
+        }
+    }
+
+    /// Monday to Friday, 09:00 until 17:00, in UTC
+    pub fn business_hours() -> Self {
+        let at = |hour| NaiveTime::from_hms_opt(hour, 0, 0).expect("Hour should be valid");
+
+        Self::new(at(9), at(17)).on([
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ])
+    }
+
+    /// Only on these days
+    #[inline]
+    pub fn on(mut self, days: impl IntoIterator<Item = Weekday>) -> Self {
+        self.days = days.into_iter().collect();
+
+        self
+    }
+
+    /// Days and hours are in this offset instead of UTC
+    #[inline]
+    pub fn with_offset(mut self, offset: FixedOffset) -> Self {
+        self.offset = offset;
+
+        self
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.offset);
+        let time = local.time();
+
+        let on_day = self.days.is_empty() || self.days.contains(&local.weekday());
+        let in_hours = match self.start <= self.end {
+            true => self.start <= time && time < self.end,
+            false => self.start <= time || time < self.end,
+        };
+
+        on_day && in_hours
+    }
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("UTC should be a valid offset")
+}
+
+/// Offset as `+07:00`
+mod offset {
+    use chrono::FixedOffset;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        offset: &FixedOffset,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(offset)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FixedOffset, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Attribute for Duration {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Duration
+    }
+}
+
+impl<Tz: chrono::TimeZone> Attribute for DateTime<Tz> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Timestamp
+    }
+}
+
+impl Attribute for NaiveDateTime {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Timestamp
+    }
+}
+
+impl Attribute for NaiveDate {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Date
+    }
+}
+
+/// RFC 3339 timestamp, `YYYY-MM-DDTHH:MM:SS` (in UTC), `YYYY-MM-DD` date (at midnight UTC) or unix seconds
+pub(crate) fn as_timestamp(value: &Value) -> Result<DateTime<Utc>, String> {
+    let invalid = || format!("{value:?} isn't a timestamp");
+
+    match unwrap_value(value) {
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .or_else(|_| {
+                s.parse::<NaiveDateTime>()
+                    .map(|timestamp| timestamp.and_utc())
+            })
+            .or_else(|_| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map(|date| date.and_time(Default::default()).and_utc())
+            })
+            .map_err(|_| invalid()),
+        value => match Number::from_value(value) {
+            Some(Number::Int(seconds)) => i64::try_from(seconds)
+                .ok()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        },
+    }
+}
+
+/// Order of two values of a temporal type, None when it isn't one or either value doesn't parse
+///
+/// Keeps `Equal`/`Greater`/`Less` chronological instead of lexical on temporal attributes
+pub(crate) fn temporal_cmp(ty: &AttributeType, a: &Value, b: &Value) -> Option<Ordering> {
+    match unwrap_optional(ty) {
+        AttributeType::Timestamp | AttributeType::Date => {
+            Some(as_timestamp(a).ok()?.cmp(&as_timestamp(b).ok()?))
+        }
+        AttributeType::Duration => {
+            let duration = |value: &Value| match unwrap_value(value) {
+                Value::String(s) => s.parse::<Duration>().ok(),
+                _ => None,
+            };
+
+            Some(duration(a)?.cmp(&duration(b)?))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,331 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    AttrPath, AttributeType, Builtin, Condition, Engine, Error, EvaluateEntity, EvaluateRequest,
+    Operator, PathSegment, Rules, SideRule, Template, check_custom, fits, is_number, is_timestamp,
+    literal_type, unwrap_optional,
+};
+
+/// Entities the rules are checked against, along with the bound element inside a quantifier
+/// and the placeholders of a template
+pub(crate) struct Scope<'a> {
+    subject: &'a str,
+    resource: &'a str,
+    /// Aliased entities of the request, without a request an alias names its entity
+    aliases: Option<&'a [(&'a str, EvaluateEntity<'a>)]>,
+    element: Option<AttributeType>,
+    params: Option<&'a BTreeMap<String, AttributeType>>,
+}
+
+impl Engine {
+    /// Check the rules against the registered entities, meant to be called when loading a policy
+    ///
+    /// Every attribute path must exist, every function must be known and get the right types
+    /// and every named condition and referenced policy must exist without cycles.
+    /// Entities that aren't registered (or have no known attributes) are skipped
+    pub fn validate(&self, subject: &str, resource: &str, rules: &Rules) -> Result<(), Error> {
+        let scope = Scope {
+            subject,
+            resource,
+            aliases: None,
+            element: None,
+            params: None,
+        };
+
+        self.validate_rules(&scope, &self.inline(rules)?)
+    }
+
+    /// Check the rules like `validate`, against the entities of the request
+    ///
+    /// Aliased entities are checked against the entity they're loaded from, eg. `owner` as a `user`,
+    /// an alias missing from the request fails with `Error::UnknownAlias`
+    pub fn validate_request(
+        &self,
+        request: &EvaluateRequest<'_>,
+        rules: &Rules,
+    ) -> Result<(), Error> {
+        let scope = Scope {
+            subject: request.subject.name,
+            resource: request.resource.name,
+            aliases: Some(&request.entities),
+            element: None,
+            params: None,
+        };
+
+        self.validate_rules(&scope, &self.inline(rules)?)
+    }
+
+    /// Check the template like `validate`, its placeholders having their declared types
+    pub fn validate_template(
+        &self,
+        subject: &str,
+        resource: &str,
+        template: &Template,
+    ) -> Result<(), Error> {
+        let scope = Scope {
+            subject,
+            resource,
+            aliases: None,
+            element: None,
+            params: Some(&template.params),
+        };
+
+        self.validate_rules(&scope, &self.inline(&template.rules)?)
+    }
+
+    fn validate_rules(&self, scope: &Scope<'_>, rules: &Rules) -> Result<(), Error> {
+        for condition in rules.0.iter().flatten() {
+            match condition {
+                Condition::Compare(rule) => {
+                    let left = self.side_type(scope, &rule.left)?;
+                    let right = self.side_type(scope, &rule.right)?;
+
+                    match &rule.operator {
+                        Operator::Custom(name) => self.operator_type(name, left, right)?,
+                        operator @ (Operator::Before | Operator::After | Operator::Within(_)) => {
+                            expect_operand(operator, &left, "a timestamp", is_timestamp)?;
+                            expect_operand(operator, &right, "a timestamp", is_timestamp)?;
+                        }
+                        operator @ (Operator::InCidr | Operator::NotInCidr) => {
+                            expect_operand(operator, &left, "an IP address", |ty| {
+                                matches!(ty, AttributeType::IpAddress | AttributeType::String)
+                            })?;
+                            expect_operand(operator, &right, "CIDR blocks", is_cidr)?;
+                        }
+                        operator => expect_comparable(operator, &left, &right)?,
+                    }
+                }
+                Condition::HasRole(rule) => {
+                    self.path_type(scope.subject, &rule.attribute)?;
+                }
+                Condition::HasPermission(rule) => {
+                    self.path_type(scope.subject, &rule.attribute)?;
+                }
+                Condition::Any(quantifier) | Condition::All(quantifier) => {
+                    let collection = self.side_type(scope, &quantifier.collection)?;
+                    let element = match unwrap_optional(&collection) {
+                        AttributeType::List(element) => *element.clone(),
+                        AttributeType::Any => AttributeType::Any,
+                        ty => {
+                            return Err(Error::InvalidOperands {
+                                operator: match condition {
+                                    Condition::Any(_) => "Any",
+                                    _ => "All",
+                                }
+                                .to_string(),
+                                reason: format!("expects a list, got {ty:?}"),
+                            });
+                        }
+                    };
+                    let scope = Scope {
+                        element: Some(element),
+                        ..*scope
+                    };
+
+                    self.validate_rules(&scope, &quantifier.rules)?;
+                }
+                Condition::Inlined(definition) => self.validate_rules(scope, &definition.rules)?,
+                Condition::Included(policy) => self.validate_rules(scope, &policy.rules)?,
+                Condition::Named(name) => return Err(Error::UnknownCondition(name.clone())),
+                Condition::Ref(id) => return Err(Error::UnknownPolicy(id.clone())),
+                Condition::HasRelation(_) | Condition::During(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Type of one side of a rule
+    pub(crate) fn side_type(
+        &self,
+        scope: &Scope<'_>,
+        side: &SideRule,
+    ) -> Result<AttributeType, Error> {
+        match side {
+            SideRule::Subject(path) => self.path_type(scope.subject, path),
+            SideRule::Object(path) => self.path_type(scope.resource, path),
+            SideRule::Entity(alias, path) => {
+                let name = match scope.aliases {
+                    Some(aliases) => aliases
+                        .iter()
+                        .find(|(key, _)| key == alias)
+                        .map(|(_, entity)| entity.name)
+                        .ok_or_else(|| Error::UnknownAlias(alias.clone()))?,
+                    None => alias.as_str(),
+                };
+
+                self.path_type(name, path)
+            }
+            SideRule::Element(path) => {
+                let element = scope.element.as_ref().ok_or(Error::UnboundElement)?;
+
+                match path {
+                    Some(path) => element.segments_type(path.segments()).ok_or_else(|| {
+                        Error::UnknownAttribute {
+                            entity: "element".to_string(),
+                            path: path.to_string(),
+                        }
+                    }),
+                    None => Ok(element.clone()),
+                }
+            }
+            SideRule::Literal(value) => Ok(literal_type(value)),
+            SideRule::Param(name) => match scope.params {
+                Some(params) => params
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| Error::UnknownParameter(name.clone())),
+                None => Err(Error::UnboundParameter(name.clone())),
+            },
+            SideRule::Arithmetic(expression) => {
+                let left = self.side_type(scope, &expression.left)?;
+                let right = self.side_type(scope, &expression.right)?;
+
+                expect_operand(expression.operator, &left, "a number", is_number)?;
+                expect_operand(expression.operator, &right, "a number", is_number)?;
+
+                let ty = match (unwrap_optional(&left), unwrap_optional(&right)) {
+                    (AttributeType::Integer, AttributeType::Integer) => AttributeType::Integer,
+                    (AttributeType::Any, _) | (_, AttributeType::Any) => AttributeType::Any,
+                    _ => AttributeType::Float,
+                };
+
+                Ok(ty)
+            }
+            SideRule::Cidr(_) => Ok(AttributeType::List(Box::new(AttributeType::Cidr))),
+            SideRule::Function(call) => {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|arg| self.side_type(scope, arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if let Some(function) = self.functions.get(call.name.as_str()) {
+                    return check_custom(function.as_ref(), &args).map_err(|reason| {
+                        Error::InvalidArguments {
+                            function: call.name.clone(),
+                            reason,
+                        }
+                    });
+                }
+
+                let builtin = Builtin::from_name(&call.name)
+                    .ok_or_else(|| Error::UnknownFunction(call.name.clone()))?;
+
+                builtin
+                    .check(&args)
+                    .map_err(|reason| Error::InvalidArguments {
+                        function: call.name.clone(),
+                        reason,
+                    })
+            }
+        }
+    }
+
+    /// Check the custom operator takes both sides and returns a bool
+    fn operator_type(
+        &self,
+        name: &str,
+        left: AttributeType,
+        right: AttributeType,
+    ) -> Result<(), Error> {
+        let function = self
+            .functions
+            .get(name)
+            .ok_or_else(|| Error::UnknownFunction(name.to_string()))?;
+        let invalid = |reason| Error::InvalidArguments {
+            function: name.to_string(),
+            reason,
+        };
+
+        let output = check_custom(function.as_ref(), &[left, right]).map_err(invalid)?;
+        if !fits(&AttributeType::Bool, &output) {
+            return Err(invalid(format!(
+                "operator should return a bool, got {output:?}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Type of the attribute at the path, `Any` when the entity's schema isn't known
+    pub(crate) fn path_type(&self, name: &str, path: &AttrPath) -> Result<AttributeType, Error> {
+        // Follow the references down to the entity owning the attribute
+        let mut name = name;
+        let mut segments = path.segments();
+        let entity = loop {
+            let Some(entity) = self.entities.get(name) else {
+                return Ok(AttributeType::Any);
+            };
+
+            match segments.split_first() {
+                Some((PathSegment::Key(key), rest)) if !rest.is_empty() => {
+                    match entity.references().iter().find(|r| r.alias == key) {
+                        Some(reference) => {
+                            name = reference.entity;
+                            segments = rest;
+                        }
+                        None => break entity,
+                    }
+                }
+                _ => break entity,
+            }
+        };
+        let schema = self.entity_schema(name, entity.as_ref());
+
+        if schema.attributes.is_empty() {
+            return Ok(AttributeType::Any);
+        }
+
+        schema
+            .segments_type(segments)
+            .ok_or_else(|| Error::UnknownAttribute {
+                entity: name.to_string(),
+                path: path.to_string(),
+            })
+    }
+}
+
+/// Check the operand type of a built-in operator, unknown types are checked while evaluating
+/// Check both sides can be compared, numbers compare with each other
+fn expect_comparable(
+    operator: impl fmt::Debug,
+    left: &AttributeType,
+    right: &AttributeType,
+) -> Result<(), Error> {
+    match (unwrap_optional(left), unwrap_optional(right)) {
+        (left, right) if is_number(left) && is_number(right) => Ok(()),
+        (left, right) if fits(left, right) => Ok(()),
+        (left, right) => Err(Error::InvalidOperands {
+            operator: format!("{operator:?}"),
+            reason: format!("can't compare {left:?} with {right:?}"),
+        }),
+    }
+}
+
+fn expect_operand(
+    operator: impl fmt::Debug,
+    ty: &AttributeType,
+    expected: &str,
+    accept: impl Fn(&AttributeType) -> bool,
+) -> Result<(), Error> {
+    match unwrap_optional(ty) {
+        AttributeType::Any => Ok(()),
+        ty if accept(ty) => Ok(()),
+        ty => Err(Error::InvalidOperands {
+            operator: format!("{operator:?}"),
+            reason: format!("expects {expected}, got {ty:?}"),
+        }),
+    }
+}
+
+/// Single block or a list of blocks
+fn is_cidr(ty: &AttributeType) -> bool {
+    match ty {
+        AttributeType::Cidr | AttributeType::String => true,
+        AttributeType::List(inner) => matches!(
+            unwrap_optional(inner),
+            AttributeType::Cidr | AttributeType::String | AttributeType::Any
+        ),
+        _ => false,
+    }
+}
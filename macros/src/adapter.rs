@@ -0,0 +1,80 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr, Path, Type, parse_macro_input};
+
+use crate::derive::crate_ident;
+
+pub fn derive_entity_adapter_impl(input: TokenStream) -> TokenStream {
+    // 1. Parse the input
+    let ast = parse_macro_input!(input as DeriveInput);
+    let struct_ident = &ast.ident;
+    let crate_ident = crate_ident();
+
+    // 2. Grab `#[abac(provider = ..., provider_name = "...", load = ..., id = ...)]`
+    let mut provider: Option<Type> = None;
+    let mut provider_name: Option<LitStr> = None;
+    let mut load: Option<Path> = None;
+    let mut id: Option<Type> = None;
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("abac")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("provider") {
+                provider = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("provider_name") {
+                provider_name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("load") {
+                load = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("id") {
+                id = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown #[abac] adapter attribute"));
+            }
+
+            Ok(())
+        });
+
+        if let Err(err) = parsed {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let (Some(provider), Some(load)) = (provider, load) else {
+        return syn::Error::new_spanned(
+            struct_ident,
+            "#[derive(EntityAdapter)] needs #[abac(provider = ..., load = ...)]",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    // The id is a UUID unless told otherwise
+    let id = match id {
+        Some(id) => quote! { #id },
+        None => quote! { #crate_ident::uuid::Uuid },
+    };
+
+    // The provider registered by its type alone unless named
+    let provider_name = provider_name.map(|name| {
+        quote! { const PROVIDER_NAME: Option<&'static str> = Some(#name); }
+    });
+
+    // 3. Emit the impl, `load` is an ordinary `async fn(Id, &Provider) -> Result<Self, E>`
+    let expanded = quote! {
+        impl #crate_ident::EntityAdapter for #struct_ident {
+            type Provider = #provider;
+            type Id = #id;
+            #provider_name
+
+            fn load_data(
+                id: Self::Id,
+                provider: &Self::Provider
+            ) -> #crate_ident::LoadResult<'_, Self> {
+                Box::pin(async move {
+                    #load(id, provider).await.map_err(#crate_ident::Error::load_error)
+                })
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
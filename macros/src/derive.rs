@@ -3,12 +3,8 @@ use proc_macro_crate::{FoundCrate, crate_name};
 use quote::{format_ident, quote};
 use syn::{DeriveInput, parse_macro_input};
 
-pub fn derive_entity_impl(input: TokenStream) -> TokenStream {
-    // 1. Parse the input
-    let ast = parse_macro_input!(input as DeriveInput);
-    let struct_ident = &ast.ident;
-
-    // 2. Figure out whether to refer to "crate" or "abac_rs"
+/// Figure out whether to refer to "crate" or "abac_rs"
+pub(crate) fn crate_ident() -> syn::Ident {
     let crate_name = match crate_name("abac-rs") {
         // If the crate name maps to itself, use "crate"
         Ok(FoundCrate::Itself) => "crate".to_string(),
@@ -18,7 +14,17 @@ pub fn derive_entity_impl(input: TokenStream) -> TokenStream {
         // fall back to "crate"
         Err(_) => "crate".to_string(),
     };
-    let crate_ident = syn::Ident::new(&crate_name, proc_macro2::Span::call_site());
+
+    syn::Ident::new(&crate_name, proc_macro2::Span::call_site())
+}
+
+pub fn derive_entity_impl(input: TokenStream) -> TokenStream {
+    // 1. Parse the input
+    let ast = parse_macro_input!(input as DeriveInput);
+    let struct_ident = &ast.ident;
+
+    // 2. Figure out whether to refer to "crate" or "abac_rs"
+    let crate_ident = crate_ident();
 
     // 3. Grab the struct’s named fields
     let fields = match &ast.data {
@@ -44,15 +50,116 @@ pub fn derive_entity_impl(input: TokenStream) -> TokenStream {
         quote! {
             map.insert(
                 #fname.to_string(),
-                #crate_ident::serde_value::to_value(&self.#ident)
-                    .expect("#crate_ident::serde_value::to_value failed")
+                #crate_ident::serde_value::to_value(&self.#ident)?
             );
         }
     });
 
-    // 6. Emit the impl, referring to either `crate::Entity` or `abac_rs::Entity`
+    // 6. Build the match arms for get_attribute, only the asked field gets serialized
+    let gen_arms = idents.clone().map(|ident| {
+        let fname = ident.to_string();
+        quote! {
+            #fname => Ok(Some(std::borrow::Cow::Owned(
+                #crate_ident::serde_value::to_value(&self.#ident)?
+            ))),
+        }
+    });
+
+    // 7. Build the typed field constants (eg. `User::AGE`) for the rules builder
+    let vis = &ast.vis;
+    let gen_attrs = fields.iter().map(|f| {
+        let ident = f.ident.as_ref().unwrap();
+        let ty = &f.ty;
+        let fname = ident.to_string();
+        let const_ident = format_ident!("{}", fname.trim_start_matches("r#").to_uppercase());
+        quote! {
+            #vis const #const_ident: #crate_ident::Attr<Self, #ty> = #crate_ident::Attr::new(#fname);
+        }
+    });
+
+    // 8. Build the attribute schemas, unknown field types become `AttributeType::Any`
+    let gen_schemas: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let fname = f.ident.as_ref().unwrap().to_string();
+            let ty = &f.ty;
+            quote! {
+                #crate_ident::AttributeSchema::new(#fname, {
+                    use #crate_ident::{KnownAttribute as _, UnknownAttribute as _};
+                    (&&#crate_ident::TypeProbe::<#ty>::new()).probe_type()
+                }),
+            }
+        })
+        .collect();
+
+    // 9. Build the match arms for attribute_type, only the asked field's type gets built
+    let gen_type_arms = fields.iter().map(|f| {
+        let fname = f.ident.as_ref().unwrap().to_string();
+        let ty = &f.ty;
+        quote! {
+            #fname => Some({
+                use #crate_ident::{KnownAttribute as _, UnknownAttribute as _};
+                (&&#crate_ident::TypeProbe::<#ty>::new()).probe_type()
+            }),
+        }
+    });
+
+    // 10. Build the references from `#[abac(ref = "entity", alias = "name")]`
+    let mut gen_references = Vec::new();
+    for field in fields {
+        let fname = field.ident.as_ref().unwrap().to_string();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("abac"))
+        {
+            let mut entity: Option<syn::LitStr> = None;
+            let mut alias: Option<syn::LitStr> = None;
+
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("ref") {
+                    entity = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("alias") {
+                    alias = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown #[abac] field attribute"));
+                }
+
+                Ok(())
+            });
+
+            if let Err(err) = parsed {
+                return err.to_compile_error().into();
+            }
+
+            let Some(entity) = entity else {
+                return syn::Error::new_spanned(attr, "#[abac] field attribute needs `ref`")
+                    .to_compile_error()
+                    .into();
+            };
+            let alias = alias.unwrap_or_else(|| entity.clone());
+
+            gen_references.push(quote! {
+                #crate_ident::Reference::new(#fname, #entity, #alias),
+            });
+        }
+    }
+
+    let refs_ident = format_ident!("__{}_REFERENCES", struct_ident.to_string().to_uppercase());
+    let gen_refs = quote! {
+        const #refs_ident: &[#crate_ident::Reference] = &[ #(#gen_references)* ];
+    };
+
+    // 11. Emit the impl, referring to either `crate::Entity` or `abac_rs::Entity`
     let expanded = quote! {
         #gen_field_names
+        #gen_refs
+
+        #[allow(dead_code)]
+        impl #struct_ident {
+            #(#gen_attrs)*
+        }
 
         impl #crate_ident::Entity for #struct_ident {
             fn to_value(&self) -> Result<
@@ -67,6 +174,43 @@ pub fn derive_entity_impl(input: TokenStream) -> TokenStream {
             fn field_names(&self) -> &'static [&'static str] {
                 #slice_ident
             }
+
+            fn get_attribute(
+                &self,
+                name: &str
+            ) -> Result<
+                Option<std::borrow::Cow<'_, #crate_ident::serde_value::Value>>,
+                #crate_ident::Error
+            > {
+                match name {
+                    #(#gen_arms)*
+                    _ => Ok(None),
+                }
+            }
+
+            fn attributes(&self) -> Vec<#crate_ident::AttributeSchema> {
+                match <Self as #crate_ident::Attribute>::attribute_type() {
+                    #crate_ident::AttributeType::Struct(attributes) => attributes,
+                    _ => Vec::new(),
+                }
+            }
+
+            fn attribute_type(&self, name: &str) -> Option<#crate_ident::AttributeType> {
+                match name {
+                    #(#gen_type_arms)*
+                    _ => None,
+                }
+            }
+
+            fn references(&self) -> &'static [#crate_ident::Reference] {
+                #refs_ident
+            }
+        }
+
+        impl #crate_ident::Attribute for #struct_ident {
+            fn attribute_type() -> #crate_ident::AttributeType {
+                #crate_ident::struct_type::<Self>(|| vec![ #(#gen_schemas)* ])
+            }
         }
     };
 
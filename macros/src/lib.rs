@@ -1,12 +1,18 @@
 // macros/src/lib.rs
 
+mod adapter;
 mod derive;
 
 use proc_macro::TokenStream;
 
-use crate::derive::derive_entity_impl;
+use crate::{adapter::derive_entity_adapter_impl, derive::derive_entity_impl};
 
-#[proc_macro_derive(Entity)]
+#[proc_macro_derive(Entity, attributes(abac))]
 pub fn derive_entity(input: TokenStream) -> TokenStream {
     derive_entity_impl(input)
 }
+
+#[proc_macro_derive(EntityAdapter, attributes(abac))]
+pub fn derive_entity_adapter(input: TokenStream) -> TokenStream {
+    derive_entity_adapter_impl(input)
+}